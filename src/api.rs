@@ -0,0 +1,236 @@
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
+
+use crate::{
+    MarkdownOptions, dedup_impls, format_generic_args, format_impl_header, format_item_signature,
+    group_items, is_excluded, is_hidden,
+};
+
+/// Renders a terse manifest of a crate's public API: the signature of every public
+/// item, grouped by module, with no docs or field tables. A type's methods and
+/// trait impls and a trait's items follow it, each marked with its path.
+///
+/// This is meant for diffing the API surface between versions rather than for
+/// reading.
+pub fn render_public_api(data: &Crate, options: &MarkdownOptions) -> String {
+    let mut output = String::from("# Public API\n\n");
+    if let Some(root_item) = data.index.get(&data.root) {
+        let root_path = root_item.name.clone().unwrap_or_default();
+        push_module(&mut output, root_item, &root_path, data, options);
+    }
+    output
+}
+
+fn push_module(
+    output: &mut String,
+    module_item: &Item,
+    module_path: &str,
+    data: &Crate,
+    options: &MarkdownOptions,
+) {
+    let ItemEnum::Module(module) = &module_item.inner else {
+        return;
+    };
+
+    let items: Vec<&Item> = group_items(&module.items, data)
+        .into_iter()
+        .flat_map(|(_, ids)| ids)
+        .filter(|id| !is_excluded(id, data, options))
+        .filter_map(|id| data.index.get(&id))
+        .filter(|item| matches!(item.visibility, Visibility::Public) && !is_hidden(item, options))
+        .collect();
+
+    let signatures: Vec<String> = items
+        .iter()
+        .filter(|item| !matches!(item.inner, ItemEnum::Module(_)))
+        .map(|item| {
+            let mut signature = String::new();
+            format_item_signature(&mut signature, item, data, options);
+            let name = item.name.as_deref().unwrap_or_default();
+            for (path, member_signature) in member_signatures(item, name, data, options) {
+                signature.push_str(&format!("\n\n// {}\n{}", path, member_signature));
+            }
+            signature
+        })
+        .collect();
+    if !signatures.is_empty() {
+        output.push_str(&format!(
+            "## `{}`\n\n```rust\n{}\n```\n\n",
+            module_path,
+            signatures.join("\n\n")
+        ));
+    }
+
+    // Then each submodule in turn, so every module's items stay together
+    for item in items {
+        if let (ItemEnum::Module(_), Some(name)) = (&item.inner, &item.name) {
+            let child_path = format!("{}::{}", module_path, name);
+            push_module(output, item, &child_path, data, options);
+        }
+    }
+}
+
+/// Returns the signatures of the associated items that are part of an item's
+/// API, keyed by their path from `item_path`: a trait's items (`Trait::item`), a
+/// type's public inherent items (`Type::item`), and its trait impls
+/// (`<Type as Trait>`) with their items (`<Type as Trait>::item`).
+///
+/// Synthetic and blanket impls are left out, as they follow from other items.
+pub(crate) fn member_signatures(
+    item: &Item,
+    item_path: &str,
+    data: &Crate,
+    options: &MarkdownOptions,
+) -> Vec<(String, String)> {
+    let mut members = Vec::new();
+    let push_items = |members: &mut Vec<(String, String)>, prefix: &str, ids: &[Id]| {
+        for member in ids.iter().filter_map(|id| data.index.get(id)) {
+            let Some(name) = &member.name else {
+                continue;
+            };
+            if is_hidden(member, options) {
+                continue;
+            }
+            let mut signature = String::new();
+            format_item_signature(&mut signature, member, data, options);
+            members.push((format!("{}::{}", prefix, name), signature));
+        }
+    };
+
+    let impls = match &item.inner {
+        ItemEnum::Trait(trait_) => {
+            push_items(&mut members, item_path, &trait_.items);
+            return members;
+        }
+        ItemEnum::Struct(struct_) => &struct_.impls,
+        ItemEnum::Enum(enum_) => &enum_.impls,
+        ItemEnum::Union(union_) => &union_.impls,
+        _ => return members,
+    };
+
+    for impl_id in dedup_impls(impls, data) {
+        let Some(ItemEnum::Impl(impl_)) = data.index.get(&impl_id).map(|item| &item.inner) else {
+            continue;
+        };
+        if impl_.is_synthetic || impl_.blanket_impl.is_some() {
+            continue;
+        }
+
+        match &impl_.trait_ {
+            None => {
+                let public_items: Vec<Id> = impl_
+                    .items
+                    .iter()
+                    .copied()
+                    .filter(|id| {
+                        data.index
+                            .get(id)
+                            .is_some_and(|item| matches!(item.visibility, Visibility::Public))
+                    })
+                    .collect();
+                push_items(&mut members, item_path, &public_items);
+            }
+            Some(trait_) => {
+                let mut trait_args = String::new();
+                if let Some(args) = &trait_.args {
+                    format_generic_args(&mut trait_args, args, data);
+                }
+                let prefix = format!(
+                    "<{} as {}{}{}>",
+                    item_path,
+                    if impl_.is_negative { "!" } else { "" },
+                    trait_.path,
+                    trait_args
+                );
+                let mut header = String::new();
+                format_impl_header(&mut header, impl_, data);
+                members.push((prefix.clone(), header));
+                push_items(&mut members, &prefix, &impl_.items);
+            }
+        }
+    }
+
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_types::{Impl, Item, ItemEnum, Type, Visibility};
+
+    use crate::MarkdownOptions;
+    use crate::tests::{
+        assoc_item, function, impl_block, impl_item, item, item_path, primitive, resolved,
+        struct_with_fields, test_crate, trait_with_items,
+    };
+
+    use super::render_public_api;
+
+    #[test]
+    fn manifest_lists_methods_trait_impls_and_trait_items() {
+        let mut_self = || Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: true,
+            type_: Box::new(Type::Generic("Self".to_string())),
+        };
+        let mut required = assoc_item(21, "req", function(vec![("x", primitive("u32"))], None));
+        if let ItemEnum::Function(function) = &mut required.inner {
+            function.has_body = false;
+        }
+        let synthetic = Impl {
+            is_synthetic: true,
+            ..impl_block(
+                Some(item_path("Send", 101, None)),
+                resolved("Counter", 1, None),
+                &[],
+            )
+        };
+        let data = test_crate(
+            &[1, 2],
+            vec![
+                item(1, "Counter", struct_with_fields(&[], &[10, 11, 12])),
+                impl_item(10, None, resolved("Counter", 1, None), &[13, 14]),
+                impl_item(
+                    11,
+                    Some(item_path("Clone", 100, None)),
+                    resolved("Counter", 1, None),
+                    &[],
+                ),
+                item(12, "", ItemEnum::Impl(synthetic)),
+                item(
+                    13,
+                    "set",
+                    function(
+                        vec![("self", mut_self()), ("value", primitive("u32"))],
+                        Some(primitive("u32")),
+                    ),
+                ),
+                Item {
+                    visibility: Visibility::Default,
+                    ..item(14, "helper", function(Vec::new(), None))
+                },
+                item(2, "Req", trait_with_items(&[21], &[])),
+                required,
+            ],
+        );
+
+        let output = render_public_api(&data, &MarkdownOptions::default());
+        assert!(
+            output.contains(
+                "// Counter::set\npub fn set(&mut self, value: u32) -> u32 { /* ... */ }\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("// <Counter as Clone>\nimpl Clone for Counter\n"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("// Req::req\nfn req(x: u32);\n"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("helper"), "{}", output);
+        assert!(!output.contains("Send"), "{}", output);
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+
+use rustdoc_types::{Crate, Item, ItemEnum, Visibility};
+
+use crate::api::member_signatures;
+use crate::{MarkdownOptions, format_item_signature, group_items, is_excluded, is_hidden};
+
+/// The groups from [`group_items`] in the order they're reported. Modules are
+/// left out, as their contents are compared item by item.
+const GROUP_ORDER: &[&str] = &[
+    "Types",
+    "Traits",
+    "Functions",
+    "Constants and Statics",
+    "Macros",
+    "Re-exports",
+    "Extern Crates",
+    "Other Items",
+];
+
+/// A public item's group and rendered signature, keyed by its path.
+type PublicApi = BTreeMap<String, (&'static str, String)>;
+
+/// Renders a report of the public API changes from `old` to `new`: the items
+/// that were added or removed, and the items whose signature changed, matched by
+/// their path and grouped by kind.
+///
+/// This is meant for release notes, and compares the same signatures as
+/// [`render_public_api`](crate::render_public_api).
+pub fn diff_crates(old: &Crate, new: &Crate, options: &MarkdownOptions) -> String {
+    let old_api = collect_public_api(old, options);
+    let new_api = collect_public_api(new, options);
+
+    let added: Vec<_> = new_api
+        .iter()
+        .filter(|(path, _)| !old_api.contains_key(*path))
+        .collect();
+    let removed: Vec<_> = old_api
+        .iter()
+        .filter(|(path, _)| !new_api.contains_key(*path))
+        .collect();
+    let changed: Vec<_> = new_api
+        .iter()
+        .filter_map(|(path, (group, new_signature))| {
+            let (_, old_signature) = old_api.get(path)?;
+            (old_signature != new_signature)
+                .then_some((path, (*group, old_signature, new_signature)))
+        })
+        .collect();
+
+    let mut output = String::from("# API Changes\n\n");
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        output.push_str("No changes to the public API.\n");
+        return output;
+    }
+
+    for (title, entries) in [("Added", &added), ("Removed", &removed)] {
+        if entries.is_empty() {
+            continue;
+        }
+        output.push_str(&format!("## {}\n\n", title));
+        for &group in GROUP_ORDER {
+            let signatures: Vec<String> = entries
+                .iter()
+                .filter(|(_, (item_group, _))| *item_group == group)
+                .map(|(path, (_, signature))| format!("// {}\n{}", path, signature))
+                .collect();
+            if !signatures.is_empty() {
+                output.push_str(&format!(
+                    "### {}\n\n```rust\n{}\n```\n\n",
+                    group,
+                    signatures.join("\n\n")
+                ));
+            }
+        }
+    }
+
+    if !changed.is_empty() {
+        output.push_str("## Changed\n\n");
+        for &group in GROUP_ORDER {
+            let entries: Vec<_> = changed
+                .iter()
+                .filter(|(_, (item_group, _, _))| *item_group == group)
+                .collect();
+            if entries.is_empty() {
+                continue;
+            }
+            output.push_str(&format!("### {}\n\n", group));
+            for (path, (_, old_signature, new_signature)) in entries {
+                output.push_str(&format!("#### `{}`\n\n```diff\n", path));
+                for line in old_signature.lines() {
+                    output.push_str(&format!("- {}\n", line));
+                }
+                for line in new_signature.lines() {
+                    output.push_str(&format!("+ {}\n", line));
+                }
+                output.push_str("```\n\n");
+            }
+        }
+    }
+
+    output
+}
+
+fn collect_public_api(data: &Crate, options: &MarkdownOptions) -> PublicApi {
+    let mut api = PublicApi::new();
+    if let Some(root_item) = data.index.get(&data.root) {
+        let root_path = root_item.name.clone().unwrap_or_default();
+        collect_module(&mut api, root_item, &root_path, data, options);
+    }
+    api
+}
+
+fn collect_module(
+    api: &mut PublicApi,
+    module_item: &Item,
+    module_path: &str,
+    data: &Crate,
+    options: &MarkdownOptions,
+) {
+    let ItemEnum::Module(module) = &module_item.inner else {
+        return;
+    };
+
+    for (group, ids) in group_items(&module.items, data) {
+        for id in ids {
+            if is_excluded(&id, data, options) {
+                continue;
+            }
+            let Some(item) = data.index.get(&id) else {
+                continue;
+            };
+            if !matches!(item.visibility, Visibility::Public) || is_hidden(item, options) {
+                continue;
+            }
+
+            // Glob re-exports are matched by what they re-export, while other
+            // unnamed items have no path to match them by
+            let name = match (&item.name, &item.inner) {
+                (Some(name), _) => name.clone(),
+                (None, ItemEnum::Use(use_item)) => format!("{}::*", use_item.source),
+                (None, _) => continue,
+            };
+            let path = format!("{}::{}", module_path, name);
+            if let ItemEnum::Module(_) = item.inner {
+                collect_module(api, item, &path, data, options);
+            } else {
+                let mut signature = String::new();
+                format_item_signature(&mut signature, item, data, options);
+                // Methods, trait impls and trait items are compared under their
+                // owner's group, so a changed method shows up on its own
+                for (member_path, member_signature) in member_signatures(item, &path, data, options)
+                {
+                    api.insert(member_path, (group, member_signature));
+                }
+                api.insert(path, (group, signature));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_types::{Crate, ItemEnum, Type};
+
+    use crate::MarkdownOptions;
+    use crate::tests::{
+        assoc_item, function, impl_item, item, primitive, resolved, struct_with_fields, test_crate,
+        trait_with_items,
+    };
+
+    use super::diff_crates;
+
+    /// Builds a crate with a `Counter::set` method and a `Req::req` required
+    /// method, both taking and returning `int`.
+    fn counter_crate(int: &str) -> Crate {
+        let mut_self = Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: true,
+            type_: Box::new(Type::Generic("Self".to_string())),
+        };
+        let mut required = assoc_item(21, "req", function(vec![("x", primitive(int))], None));
+        if let ItemEnum::Function(function) = &mut required.inner {
+            function.has_body = false;
+        }
+        test_crate(
+            &[1, 2],
+            vec![
+                item(1, "Counter", struct_with_fields(&[], &[10])),
+                impl_item(10, None, resolved("Counter", 1, None), &[11]),
+                item(
+                    11,
+                    "set",
+                    function(
+                        vec![("self", mut_self), ("value", primitive(int))],
+                        Some(primitive(int)),
+                    ),
+                ),
+                item(2, "Req", trait_with_items(&[21], &[])),
+                required,
+            ],
+        )
+    }
+
+    #[test]
+    fn changed_methods_and_trait_items_are_reported() {
+        let options = MarkdownOptions::default();
+        let old = counter_crate("u32");
+
+        assert_eq!(
+            diff_crates(&old, &old, &options),
+            "# API Changes\n\nNo changes to the public API.\n"
+        );
+
+        let output = diff_crates(&old, &counter_crate("u64"), &options);
+        assert!(
+            output.contains(
+                "#### `demo::Counter::set`\n\n```diff\n\
+                 - pub fn set(&mut self, value: u32) -> u32 { /* ... */ }\n\
+                 + pub fn set(&mut self, value: u64) -> u64 { /* ... */ }\n```\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains(
+                "#### `demo::Req::req`\n\n```diff\n- fn req(x: u32);\n+ fn req(x: u64);\n```\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(!output.contains("## Added"), "{}", output);
+        assert!(!output.contains("## Removed"), "{}", output);
+    }
+}
@@ -1,51 +1,817 @@
+mod api;
+mod diff;
+mod model;
+pub mod placeholders;
+mod stats;
+mod text;
+mod unresolved;
+
+use std::collections::HashSet;
+
+pub use api::render_public_api;
+pub use diff::diff_crates;
+pub use model::{DocItem, DocModel};
+pub use stats::DocStats;
+pub use text::markdown_to_plain_text;
+pub use unresolved::unresolved_paths;
+
+use placeholders::{
+    FIELDS_OMITTED, FIELDS_OMITTED_CELL, OUTPUT_TRUNCATED_NOTE, PRIVATE_FIELD, PRIVATE_FIELD_CELL,
+    PRIVATE_FIELD_TYPE_CELL, PRIVATE_FIELDS_CELL, STRIPPED_MODULE_NOTE, VARIANTS_OMITTED,
+    VARIANTS_OMITTED_NOTE,
+};
+
 use rustdoc_types::{
-    Abi, AssocItemConstraintKind, Crate, Enum, GenericArg, GenericArgs, GenericBound,
-    GenericParamDefKind, Generics, Id, Impl, Item, ItemEnum, MacroKind, Module,
-    PreciseCapturingArg, Struct, StructKind, Term, Trait, TraitBoundModifier, Type, Union,
-    VariantKind, Visibility, WherePredicate,
+    Abi, AssocItemConstraintKind, Attribute, Crate, Enum, GenericArg, GenericArgs, GenericBound,
+    GenericParamDef, GenericParamDefKind, Generics, Id, Impl, Item, ItemEnum, ItemKind, MacroKind,
+    Module, PreciseCapturingArg, ProcMacro, Struct, StructKind, Term, Trait, TraitBoundModifier,
+    Type, Union, VariantKind, Visibility, WherePredicate,
 };
 
+/// Languages of fenced code blocks that hold diagram sources rather than code.
+const DIAGRAM_LANGUAGES: &[&str] = &["mermaid", "plantuml", "dot", "graphviz"];
+
+/// The widest a line can get with a where clause kept on it, rather than broken
+/// out onto lines of its own.
+const INLINE_WHERE_CLAUSE_MAX_WIDTH: usize = 80;
+
+/// Options controlling how rustdoc JSON is rendered to Markdown.
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// How fenced diagram blocks (e.g. ```` ```mermaid ````) in doc comments are rendered.
+    pub diagram_blocks: DiagramBlocks,
+    /// Render function parameters as types only, e.g. `fn insert(&mut self, K, V)`.
+    /// The `self` receiver is always kept.
+    pub elide_param_names: bool,
+    /// The crate's Rust edition, shown in the crate header. This isn't part of
+    /// the rustdoc JSON, so it has to be supplied by the caller.
+    pub edition: Option<String>,
+    /// The crate's minimum supported Rust version, shown in the crate header.
+    pub msrv: Option<String>,
+    /// Skip items without a doc comment, except modules, which may still contain
+    /// documented items. Fields and variants are always shown.
+    pub only_documented: bool,
+    /// The most implementors listed for a trait before the rest are summarized
+    /// as a count, or `None` to list them all. Defaults to 50.
+    pub max_implementors: Option<usize>,
+    /// Prefix each tuple struct field in the signature with a comment holding its
+    /// index, e.g. `struct Point(/* 0 */ pub f64, /* 1 */ pub f64);`, to match it
+    /// up with the fields table.
+    pub tuple_field_indices: bool,
+    /// Include the signature code block under each item's docs. Turning this off
+    /// gives a compact, prose-first overview. Defaults to `true`.
+    pub include_signatures: bool,
+    /// Render re-exported items in full where they're re-exported, under the name
+    /// they're re-exported as, instead of just the `pub use`. Re-exported modules are
+    /// still shown as re-exports.
+    pub flatten_reexports: bool,
+    /// Remove the doctest lines rustdoc hides (those starting with `# `) from Rust
+    /// code blocks in docs.
+    pub strip_doc_tests: bool,
+    /// Glob patterns for the canonical paths of items to leave out, e.g.
+    /// `my_crate::internal::*`. A pattern ending in `::*` also matches the module
+    /// itself, and leaving out a module leaves out everything in it.
+    pub exclude: Vec<String>,
+    /// Stop rendering further top-level items once the output reaches this many
+    /// bytes, ending it with a note saying it was truncated. Items are never cut
+    /// off partway, so the output can run somewhat past the limit.
+    pub max_output_bytes: Option<usize>,
+    /// How multi-line docs are fit into field table cells.
+    pub table_doc_mode: TableDocMode,
+    /// Add a line counting the crate's modules, structs, enums, traits and
+    /// functions to the crate header.
+    pub include_stats: bool,
+    /// The top-level heading of the document. Defaults to the crate's name, or
+    /// "Crate Documentation" if the crate root has no name.
+    pub title: Option<String>,
+    /// How many spaces to indent each level of a nested list, including the code
+    /// blocks of method listings. Some renderers need 4 for a code block to stay
+    /// inside its list item.
+    pub list_indent: usize,
+    /// Show the rustdoc JSON format version in the crate header.
+    pub show_format_version: bool,
+    /// Keep nesting the headings of submodules and their contents below their
+    /// parent's heading, up to level 6, instead of giving every module a level 2
+    /// heading and restarting its contents at level 3.
+    pub preserve_module_depth: bool,
+    /// How many levels of modules to document, counting the crate root as depth
+    /// 0. The submodules of a module at this depth are listed in a note instead
+    /// of being rendered. `None` documents every module.
+    pub max_depth: Option<usize>,
+    /// Whether items are introduced with headings or as bold definition terms.
+    pub heading_style: HeadingStyle,
+    /// Markdown appended after all items, such as a "generated by" line. `{date}`
+    /// is replaced with the current UTC date and `{version}` with the crate's
+    /// version.
+    pub footer: Option<String>,
+    /// A base URL to resolve relative image and link URLs in docs against, such
+    /// as a repository's raw file URL, so they keep working wherever the Markdown
+    /// ends up. Intra-doc links to Rust paths are left alone.
+    pub doc_asset_base_url: Option<String>,
+    /// List the methods of each impl alphabetically rather than in source order.
+    pub sort_methods: bool,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            diagram_blocks: DiagramBlocks::default(),
+            elide_param_names: false,
+            edition: None,
+            msrv: None,
+            only_documented: false,
+            max_implementors: Some(50),
+            tuple_field_indices: false,
+            include_signatures: true,
+            flatten_reexports: false,
+            strip_doc_tests: false,
+            exclude: Vec::new(),
+            max_output_bytes: None,
+            table_doc_mode: TableDocMode::default(),
+            include_stats: false,
+            title: None,
+            list_indent: 2,
+            show_format_version: true,
+            preserve_module_depth: false,
+            max_depth: None,
+            heading_style: HeadingStyle::default(),
+            footer: None,
+            doc_asset_base_url: None,
+            sort_methods: false,
+        }
+    }
+}
+
+/// How multi-line docs are fit into a single Markdown table cell, such as in the
+/// fields table of a struct.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableDocMode {
+    /// Keep only the first line, dropping the rest of the docs.
+    #[default]
+    FirstLineOnly,
+    /// Keep every line, separated by HTML `<br>` tags.
+    HtmlBreaks,
+    /// Keep every line, joined with spaces into plain text without any HTML.
+    Escaped,
+}
+
+/// How each item is introduced in the output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingStyle {
+    /// A heading one level below its group, e.g. ``### Struct `Foo` ``.
+    #[default]
+    Headings,
+    /// A bold term followed by the item's docs and signature, which keeps deeply
+    /// nested crates from running out of heading levels. Modules keep their
+    /// headings.
+    DefinitionList,
+}
+
+/// How fenced diagram blocks in doc comments are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagramBlocks {
+    /// Emit diagram blocks unchanged, for renderers that draw them natively.
+    #[default]
+    Preserve,
+    /// Precede each diagram block with a label naming its diagram language, so it
+    /// isn't mistaken for code by renderers that show it as a plain code block.
+    Label,
+}
+
+impl MarkdownOptions {
+    /// Creates options with every setting at its default.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how fenced diagram blocks in doc comments are rendered.
+    pub fn diagram_blocks(mut self, diagram_blocks: DiagramBlocks) -> Self {
+        self.diagram_blocks = diagram_blocks;
+        self
+    }
+
+    /// Sets whether function parameters are rendered as types only.
+    pub fn elide_param_names(mut self, elide_param_names: bool) -> Self {
+        self.elide_param_names = elide_param_names;
+        self
+    }
+
+    /// Sets the crate's Rust edition, shown in the crate header.
+    pub fn edition(mut self, edition: impl Into<String>) -> Self {
+        self.edition = Some(edition.into());
+        self
+    }
+
+    /// Sets the crate's minimum supported Rust version, shown in the crate header.
+    pub fn msrv(mut self, msrv: impl Into<String>) -> Self {
+        self.msrv = Some(msrv.into());
+        self
+    }
+
+    /// Sets whether items without a doc comment are skipped.
+    pub fn only_documented(mut self, only_documented: bool) -> Self {
+        self.only_documented = only_documented;
+        self
+    }
+
+    /// Sets the most implementors listed for a trait, or `None` to list them all.
+    pub fn max_implementors(mut self, max_implementors: Option<usize>) -> Self {
+        self.max_implementors = max_implementors;
+        self
+    }
+
+    /// Sets whether tuple struct fields are prefixed with their index in signatures.
+    pub fn tuple_field_indices(mut self, tuple_field_indices: bool) -> Self {
+        self.tuple_field_indices = tuple_field_indices;
+        self
+    }
+
+    /// Sets whether each item's signature code block is included.
+    pub fn include_signatures(mut self, include_signatures: bool) -> Self {
+        self.include_signatures = include_signatures;
+        self
+    }
+
+    /// Sets whether re-exported items are rendered in full at their re-export site.
+    pub fn flatten_reexports(mut self, flatten_reexports: bool) -> Self {
+        self.flatten_reexports = flatten_reexports;
+        self
+    }
+
+    /// Sets whether hidden doctest lines are removed from Rust code blocks in docs.
+    pub fn strip_doc_tests(mut self, strip_doc_tests: bool) -> Self {
+        self.strip_doc_tests = strip_doc_tests;
+        self
+    }
+
+    /// Adds a glob pattern for the canonical paths of items to leave out.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Sets the size in bytes past which no further top-level items are rendered.
+    pub fn max_output_bytes(mut self, max_output_bytes: Option<usize>) -> Self {
+        self.max_output_bytes = max_output_bytes;
+        self
+    }
+
+    /// Sets how multi-line docs are fit into field table cells.
+    pub fn table_doc_mode(mut self, table_doc_mode: TableDocMode) -> Self {
+        self.table_doc_mode = table_doc_mode;
+        self
+    }
+
+    /// Sets whether the crate header includes a line of item counts.
+    pub fn include_stats(mut self, include_stats: bool) -> Self {
+        self.include_stats = include_stats;
+        self
+    }
+
+    /// Sets the top-level heading of the document.
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Sets how many spaces to indent each level of a nested list.
+    pub fn list_indent(mut self, list_indent: usize) -> Self {
+        self.list_indent = list_indent;
+        self
+    }
+
+    /// Sets whether the crate header shows the rustdoc JSON format version.
+    pub fn show_format_version(mut self, show_format_version: bool) -> Self {
+        self.show_format_version = show_format_version;
+        self
+    }
+
+    /// Sets whether submodules and their contents keep nesting below their parent's
+    /// heading.
+    pub fn preserve_module_depth(mut self, preserve_module_depth: bool) -> Self {
+        self.preserve_module_depth = preserve_module_depth;
+        self
+    }
+
+    /// Sets how many levels of modules to document below the crate root.
+    pub fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets whether items are introduced with headings or as bold definition terms.
+    pub fn heading_style(mut self, heading_style: HeadingStyle) -> Self {
+        self.heading_style = heading_style;
+        self
+    }
+
+    /// Sets Markdown to append after all items, with `{date}` and `{version}`
+    /// substituted.
+    pub fn footer(mut self, footer: impl Into<String>) -> Self {
+        self.footer = Some(footer.into());
+        self
+    }
+
+    /// Sets the base URL relative image and link URLs in docs are resolved against.
+    pub fn doc_asset_base_url(mut self, doc_asset_base_url: impl Into<String>) -> Self {
+        self.doc_asset_base_url = Some(doc_asset_base_url.into());
+        self
+    }
+
+    /// Sets whether impl methods are listed alphabetically rather than in source
+    /// order.
+    pub fn sort_methods(mut self, sort_methods: bool) -> Self {
+        self.sort_methods = sort_methods;
+        self
+    }
+
+    /// Renders the whole crate to Markdown with these options.
+    pub fn render(&self, data: &Crate) -> String {
+        render_markdown(data, data.index.get(&data.root), self)
+    }
+}
+
 pub fn rustdoc_json_to_markdown(data: Crate) -> String {
+    MarkdownOptions::default().render(&data)
+}
+
+pub fn rustdoc_json_to_markdown_with_options(data: Crate, options: &MarkdownOptions) -> String {
+    options.render(&data)
+}
+
+/// Renders only the module at `path` (e.g. `my_crate::net::http`) and its
+/// descendants, as if it were the crate root.
+///
+/// Returns `None` if the path doesn't resolve to a module of this crate; use
+/// [`closest_module_paths`] to suggest alternatives.
+pub fn render_module_subtree(
+    data: &Crate,
+    path: &str,
+    options: &MarkdownOptions,
+) -> Option<String> {
+    let module_item = find_module(data, path)?;
+    Some(render_markdown(data, Some(module_item), options))
+}
+
+/// Finds the module of this crate at `path`, e.g. `my_crate::net::http`.
+fn find_module<'a>(data: &'a Crate, path: &str) -> Option<&'a Item> {
+    data.paths
+        .iter()
+        .filter(|(_, summary)| summary.crate_id == 0 && summary.kind == ItemKind::Module)
+        .find(|(_, summary)| summary.path.join("::") == path)
+        .and_then(|(id, _)| data.index.get(id))
+}
+
+/// Returns up to `limit` module paths of this crate that are closest to `path`,
+/// closest first.
+pub fn closest_module_paths(data: &Crate, path: &str, limit: usize) -> Vec<String> {
+    let mut module_paths: Vec<(usize, String)> = data
+        .paths
+        .values()
+        .filter(|summary| summary.crate_id == 0 && summary.kind == ItemKind::Module)
+        .map(|summary| {
+            let module_path = summary.path.join("::");
+            (edit_distance(&module_path, path), module_path)
+        })
+        .collect();
+    module_paths.sort();
+    module_paths
+        .into_iter()
+        .take(limit)
+        .map(|(_, module_path)| module_path)
+        .collect()
+}
+
+/// Computes the Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut row = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = prev_row[j] + usize::from(a_char != *b_char);
+            row[j + 1] = substitution.min(prev_row[j + 1] + 1).min(row[j] + 1);
+        }
+        prev_row = row;
+    }
+
+    prev_row[b.len()]
+}
+
+fn render_markdown(data: &Crate, root_item: Option<&Item>, options: &MarkdownOptions) -> String {
     let mut output = String::new();
 
     // Add crate header and basic info
-    output.push_str("# Crate Documentation\n\n");
+    let title = options
+        .title
+        .clone()
+        .or_else(|| data.index.get(&data.root)?.name.clone())
+        .unwrap_or_else(|| "Crate Documentation".to_string());
+    output.push_str(&format!("# {}\n\n", title));
 
     if let Some(version) = &data.crate_version {
         output.push_str(&format!("**Version:** {}\n\n", version));
     }
 
-    output.push_str(&format!("**Format Version:** {}\n\n", data.format_version));
+    if let Some(url) = data
+        .index
+        .get(&data.root)
+        .and_then(|root| root.attrs.iter().find_map(html_root_url))
+    {
+        output.push_str(&format!("**Repository:** <{}>\n\n", url));
+    }
+
+    if let Some(edition) = &options.edition {
+        output.push_str(&format!("**Edition:** {}\n\n", edition));
+    }
+
+    if let Some(msrv) = &options.msrv {
+        output.push_str(&format!("**MSRV:** {}\n\n", msrv));
+    }
+
+    if options.show_format_version {
+        output.push_str(&format!("**Format Version:** {}\n\n", data.format_version));
+    }
+
+    if options.include_stats
+        && let Some(root_item) = root_item
+    {
+        let summary = DocStats::collect_from(root_item, data, options).summary();
+        if !summary.is_empty() {
+            output.push_str(&format!("**Items:** {}\n\n", summary));
+        }
+    }
 
     // Process the root module to start
-    let root_id = data.root;
-    if let Some(root_item) = data.index.get(&root_id) {
-        if let ItemEnum::Module(module) = &root_item.inner {
-            if let Some(name) = &root_item.name {
-                output.push_str(&format!("# Module `{}`\n\n", name));
-            } else if module.is_crate {
-                output.push_str("# Crate Root\n\n");
-            }
+    if let Some(root_item) = root_item
+        && let ItemEnum::Module(module) = &root_item.inner
+    {
+        // The crate's name is already the title unless another was given
+        let is_title = options.title.is_none() && root_item.id == data.root;
+        if let Some(name) = root_item.name.as_ref().filter(|_| !is_title) {
+            output.push_str(&format!("# Module `{}`\n\n", name));
+        } else if root_item.name.is_none() && module.is_crate {
+            output.push_str("# Crate Root\n\n");
+        }
 
-            // Add root documentation if available
-            if let Some(docs) = &root_item.docs {
-                output.push_str(&format!("{}\n\n", docs));
-            }
+        // Add root documentation if available
+        if let Some(docs) = &root_item.docs {
+            output.push_str(&format!("{}\n\n", render_docs(docs, 1, options)));
+        }
+
+        // Process all items in the module with consistent heading levels
+        // starting at level 2 for top-level categories
+        let items = items_within_depth(&mut output, &root_item.id, module, data, options);
+        process_items(&mut output, &items, data, options, 2);
+    }
 
-            // Process all items in the module with consistent heading levels
-            // starting at level 2 for top-level categories
-            process_items(&mut output, &module.items, &data, 2);
+    if let Some(footer) = options
+        .footer
+        .as_deref()
+        .filter(|footer| !footer.trim().is_empty())
+    {
+        if !output.ends_with("\n\n") {
+            output.push('\n');
         }
+        let footer = footer
+            .replace("{date}", &utc_date_today())
+            .replace("{version}", data.crate_version.as_deref().unwrap_or(""));
+        output.push_str(&format!("{}\n", footer.trim_end()));
     }
 
     output
 }
 
-fn process_items(output: &mut String, item_ids: &[Id], data: &Crate, level: usize) {
+/// Returns the current UTC date as `YYYY-MM-DD`.
+fn utc_date_today() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+
+    // Convert days since the epoch to a civil date (Howard Hinnant's algorithm)
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn process_items(
+    output: &mut String,
+    item_ids: &[Id],
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
     // Cap heading level at 6 (maximum valid Markdown heading level)
     let heading_level = std::cmp::min(level, 6);
 
-    // Group items by kind for better organization
+    // Process each group in order
+    for (title, ids) in group_items(&rendered_items(item_ids, data, options), data) {
+        if truncate_at_budget(output, options, level) {
+            return;
+        }
+        output.push_str(&format!("{} {}\n\n", "#".repeat(heading_level), title));
+        for id in ids {
+            if truncate_at_budget(output, options, level) {
+                return;
+            }
+            process_item(
+                output,
+                data.index.get(&id).unwrap(),
+                data,
+                options,
+                level + 1,
+            );
+        }
+    }
+}
+
+/// Returns the items of a module that are rendered under `options`, leaving out
+/// excluded and hidden items. Modules are kept even when undocumented, as they
+/// may contain documented items.
+fn rendered_items(item_ids: &[Id], data: &Crate, options: &MarkdownOptions) -> Vec<Id> {
+    item_ids
+        .iter()
+        .copied()
+        .filter(|id| match data.index.get(id) {
+            Some(_) if is_excluded(id, data, options) => false,
+            Some(item) => matches!(item.inner, ItemEnum::Module(_)) || !is_hidden(item, options),
+            None => false,
+        })
+        .collect()
+}
+
+/// Checks the `max_output_bytes` budget before rendering another top-level item,
+/// appending a note and returning `true` if it's been used up.
+///
+/// Only top-level items (those at `level` 2, directly under the root module) are
+/// checked, so the output is never cut off partway through an item.
+fn truncate_at_budget(output: &mut String, options: &MarkdownOptions, level: usize) -> bool {
+    match options.max_output_bytes {
+        Some(max_output_bytes) if level == 2 && output.len() >= max_output_bytes => {
+            output.push_str(&format!("{}\n", OUTPUT_TRUNCATED_NOTE));
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Returns whether an item has a non-empty doc comment.
+fn has_docs(item: &Item) -> bool {
+    item.docs
+        .as_deref()
+        .is_some_and(|docs| !docs.trim().is_empty())
+}
+
+/// Returns whether an item should be left out of the output under `options`.
+fn is_hidden(item: &Item, options: &MarkdownOptions) -> bool {
+    options.only_documented && !has_docs(item)
+}
+
+/// Returns whether an item's canonical path matches one of the `exclude` patterns.
+/// Dropping an excluded module from its parent drops everything under it too.
+fn is_excluded(id: &Id, data: &Crate, options: &MarkdownOptions) -> bool {
+    if options.exclude.is_empty() {
+        return false;
+    }
+    let Some(summary) = data.paths.get(id) else {
+        return false;
+    };
+
+    let path = summary.path.join("::");
+    options.exclude.iter().any(|pattern| {
+        glob_matches(pattern, &path)
+            || pattern
+                .strip_suffix("::*")
+                .is_some_and(|module_path| glob_matches(module_path, &path))
+    })
+}
+
+/// Matches `text` against a glob pattern where `*` matches any run of characters,
+/// including `::`, and `?` matches any single character.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    // Greedy matching, backtracking to the last `*` on a mismatch
+    let (mut p, mut t) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            last_star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = last_star {
+            p = star_p + 1;
+            t = star_t + 1;
+            last_star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Resolves a non-glob re-export to the local item it re-exports, following
+/// re-exports of re-exports.
+///
+/// Modules aren't resolved, since inlining one would render its own re-exports and
+/// could recurse forever on cyclic re-exports; neither are re-export cycles.
+fn reexport_target<'a>(item: &'a Item, data: &'a Crate) -> Option<&'a Item> {
+    if !matches!(item.inner, ItemEnum::Use(_)) {
+        return None;
+    }
+
+    let mut visited = HashSet::new();
+    let mut current = item;
+    while let ItemEnum::Use(use_item) = &current.inner {
+        if use_item.is_glob {
+            return None;
+        }
+        let target_id = use_item.id?;
+        if !visited.insert(target_id) {
+            return None;
+        }
+        current = data.index.get(&target_id)?;
+    }
+
+    match &current.inner {
+        ItemEnum::Module(_) => None,
+        _ => Some(current),
+    }
+}
+
+/// Returns the first non-empty line of an item's docs, to show next to it in lists.
+fn doc_summary(item: &Item) -> Option<&str> {
+    item.docs
+        .as_deref()?
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+}
+
+/// Returns the URL in a `#![doc(html_root_url = "..")]` attribute, or `None` for
+/// any other attribute.
+fn html_root_url(attr: &Attribute) -> Option<&str> {
+    let Attribute::Other(attr) = attr else {
+        return None;
+    };
+
+    let attr = attr.trim();
+    attr.strip_prefix("#![")
+        .or_else(|| attr.strip_prefix("#["))?
+        .strip_suffix(']')?
+        .trim()
+        .strip_prefix("doc")?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .trim()
+        .strip_prefix("html_root_url")?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')
+}
+
+/// Returns the aliases in a `#[doc(alias = "..")]` or `#[doc(alias("..", ".."))]`
+/// attribute, or `None` for any other attribute.
+fn doc_aliases(attr: &Attribute) -> Option<Vec<String>> {
+    let Attribute::Other(attr) = attr else {
+        return None;
+    };
+
+    let args = attr
+        .trim()
+        .strip_prefix("#[")?
+        .strip_suffix(']')?
+        .trim()
+        .strip_prefix("doc")?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?
+        .trim()
+        .strip_prefix("alias")?
+        .trim_start();
+    if !args.starts_with(['=', '(']) {
+        return None;
+    }
+
+    // The aliases are the string literals, i.e. every other `"`-separated part
+    Some(
+        args.split('"')
+            .skip(1)
+            .step_by(2)
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Returns a note explaining what `#[non_exhaustive]` means for a struct, enum or
+/// variant that's marked with it.
+fn non_exhaustive_note(item: &Item) -> Option<&'static str> {
+    if !item.attrs.contains(&Attribute::NonExhaustive) {
+        return None;
+    }
+    match &item.inner {
+        ItemEnum::Struct(_) => Some(
+            "This struct is marked `#[non_exhaustive]`: it can't be constructed with a struct literal or destructured without `..` outside its crate.",
+        ),
+        ItemEnum::Enum(_) => Some(
+            "This enum is marked `#[non_exhaustive]`: matches on it outside its crate need a wildcard arm, as variants may be added.",
+        ),
+        ItemEnum::Variant(_) => Some(
+            "This variant is marked `#[non_exhaustive]`: it can't be constructed or exhaustively matched outside its crate.",
+        ),
+        _ => None,
+    }
+}
+
+/// Returns the `#[must_use]` note for an item, including the attribute's message
+/// if it has one.
+fn must_use_note(item: &Item) -> Option<String> {
+    item.attrs.iter().find_map(|attr| match attr {
+        Attribute::MustUse {
+            reason: Some(reason),
+        } => Some(format!("**Must use:** {}", reason)),
+        Attribute::MustUse { reason: None } => Some("**Must use**".to_string()),
+        _ => None,
+    })
+}
+
+/// Writes a list item holding a code block with `signature`, followed by the
+/// item's doc summary, indented `depth` levels deep.
+fn push_code_list_item(
+    output: &mut String,
+    signature: &str,
+    summary: Option<&str>,
+    depth: usize,
+    options: &MarkdownOptions,
+) {
+    let marker_indent = " ".repeat(options.list_indent * depth);
+    let content_indent = " ".repeat(options.list_indent * (depth + 1));
+    output.push_str(&format!("{}- ```rust\n", marker_indent));
+    for line in signature.lines() {
+        output.push_str(&format!("{}{}\n", content_indent, line));
+    }
+    output.push_str(&format!("{}```", content_indent));
+    if let Some(summary) = summary {
+        output.push_str(&format!("\n{}{}", content_indent, summary));
+    }
+    output.push_str("\n\n");
+}
+
+/// Returns a note for a `#[stable(since = "..")]` or `#[unstable(feature = "..")]`
+/// attribute, or `None` for any other attribute.
+fn stability_note(attr: &Attribute) -> Option<String> {
+    let Attribute::Other(attr) = attr else {
+        return None;
+    };
+
+    let attr = attr.trim().strip_prefix("#[")?.strip_suffix(']')?.trim();
+    let (name, args) = attr.split_once('(')?;
+    let args = args.strip_suffix(')')?;
+    match name.trim() {
+        "stable" => Some(match attr_arg(args, "since") {
+            Some(since) => format!("Stable since {}", since),
+            None => "Stable".to_string(),
+        }),
+        "unstable" => Some(match attr_arg(args, "feature") {
+            Some(feature) => format!("Unstable (feature `{}`)", feature),
+            None => "Unstable".to_string(),
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the string value of `key = ".."` in an attribute's comma-separated args.
+fn attr_arg<'a>(args: &'a str, key: &str) -> Option<&'a str> {
+    args.split(',').find_map(|arg| {
+        let (arg_key, value) = arg.split_once('=')?;
+        (arg_key.trim() == key).then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Groups items by kind for better organization, returning the title and items
+/// of each non-empty group in the order the groups should be rendered.
+fn group_items(item_ids: &[Id], data: &Crate) -> Vec<(&'static str, Vec<Id>)> {
     let mut modules = Vec::new();
     let mut types = Vec::new();
     let mut traits = Vec::new();
@@ -53,6 +819,7 @@ fn process_items(output: &mut String, item_ids: &[Id], data: &Crate, level: usiz
     let mut constants = Vec::new();
     let mut macros = Vec::new();
     let mut reexports = Vec::new(); // New category for re-exports
+    let mut extern_crates = Vec::new();
     let mut other_items = Vec::new();
 
     for &id in item_ids {
@@ -68,78 +835,79 @@ fn process_items(output: &mut String, item_ids: &[Id], data: &Crate, level: usiz
                 ItemEnum::Constant { .. } | ItemEnum::Static(_) => constants.push(id),
                 ItemEnum::Macro(_) | ItemEnum::ProcMacro(_) => macros.push(id),
                 ItemEnum::Use(_) => reexports.push(id), // Categorize re-exports
+                ItemEnum::ExternCrate { .. } => extern_crates.push(id),
                 _ => other_items.push(id),
             }
         }
     }
 
-    // Process each group in order
-    if !modules.is_empty() {
-        output.push_str(&format!("{} Modules\n\n", "#".repeat(heading_level)));
-        for id in modules {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
-    }
-
-    if !types.is_empty() {
-        output.push_str(&format!("{} Types\n\n", "#".repeat(heading_level)));
-        for id in types {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
-    }
-
-    if !traits.is_empty() {
-        output.push_str(&format!("{} Traits\n\n", "#".repeat(heading_level)));
-        for id in traits {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
-    }
-
-    if !functions.is_empty() {
-        output.push_str(&format!("{} Functions\n\n", "#".repeat(heading_level)));
-        for id in functions {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
-    }
-
-    if !constants.is_empty() {
-        output.push_str(&format!(
-            "{} Constants and Statics\n\n",
-            "#".repeat(heading_level)
-        ));
-        for id in constants {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
-    }
-
-    if !macros.is_empty() {
-        output.push_str(&format!("{} Macros\n\n", "#".repeat(heading_level)));
-        for id in macros {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
-    }
+    [
+        ("Modules", modules),
+        ("Types", types),
+        ("Traits", traits),
+        ("Functions", functions),
+        ("Constants and Statics", constants),
+        ("Macros", macros),
+        ("Re-exports", reexports),
+        ("Extern Crates", extern_crates),
+        ("Other Items", other_items),
+    ]
+    .into_iter()
+    .filter(|(_, ids)| !ids.is_empty())
+    .collect()
+}
 
-    if !reexports.is_empty() {
-        output.push_str(&format!("{} Re-exports\n\n", "#".repeat(heading_level)));
-        for id in reexports {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
+/// Returns a short, stable name for the kind of an item, e.g. `"struct"`.
+fn item_kind_name(item: &Item) -> &'static str {
+    match &item.inner {
+        ItemEnum::Module(_) => "module",
+        ItemEnum::ExternCrate { .. } => "extern_crate",
+        ItemEnum::Use(_) => "use",
+        ItemEnum::Union(_) => "union",
+        ItemEnum::Struct(_) => "struct",
+        ItemEnum::StructField(_) => "struct_field",
+        ItemEnum::Enum(_) => "enum",
+        ItemEnum::Variant(_) => "variant",
+        ItemEnum::Function(_) => "function",
+        ItemEnum::Trait(_) => "trait",
+        ItemEnum::TraitAlias(_) => "trait_alias",
+        ItemEnum::Impl(_) => "impl",
+        ItemEnum::TypeAlias(_) => "type_alias",
+        ItemEnum::Constant { .. } => "constant",
+        ItemEnum::Static(_) => "static",
+        ItemEnum::ExternType => "extern_type",
+        ItemEnum::Macro(_) => "macro",
+        ItemEnum::ProcMacro(_) => "proc_macro",
+        ItemEnum::Primitive(_) => "primitive",
+        ItemEnum::AssocConst { .. } => "assoc_const",
+        ItemEnum::AssocType { .. } => "assoc_type",
     }
+}
 
-    if !other_items.is_empty() {
-        output.push_str(&format!("{} Other Items\n\n", "#".repeat(heading_level)));
-        for id in other_items {
-            process_item(output, data.index.get(&id).unwrap(), data, level + 1);
-        }
+fn process_item(
+    output: &mut String,
+    item: &Item,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
+    // Inline the re-exported item in place of the re-export, under the name it's
+    // re-exported as
+    if options.flatten_reexports
+        && let Some(target) = reexport_target(item, data)
+    {
+        let mut inlined = target.clone();
+        inlined.name = item.name.clone().or_else(|| target.name.clone());
+        process_item(output, &inlined, data, options, level);
+        return;
     }
-}
 
-fn process_item(output: &mut String, item: &Item, data: &Crate, level: usize) {
     // Cap heading level at 6 (maximum valid Markdown heading level)
     let heading_level = std::cmp::min(level, 6);
     let heading = "#".repeat(heading_level);
 
     // Add item heading with name and kind
+    let heading_start = output.len();
     match &item.inner {
         // Check for re-exports first, regardless of whether they have a name
         ItemEnum::Use(use_item) => {
@@ -173,7 +941,11 @@ fn process_item(output: &mut String, item: &Item, data: &Crate, level: usize) {
             // Handle all other items as before
             if let Some(name) = &item.name {
                 match &item.inner {
-                    // For modules, always use a consistent level (level 2) to ensure they stand out
+                    // For modules, always use a consistent level (level 2) to ensure they
+                    // stand out, unless their depth is kept
+                    ItemEnum::Module(_) if options.preserve_module_depth => {
+                        output.push_str(&format!("{} Module `{}`\n\n", heading, name))
+                    }
                     ItemEnum::Module(_) => output.push_str(&format!("## Module `{}`\n\n", name)),
                     ItemEnum::Struct(_) => {
                         output.push_str(&format!("{} Struct `{}`\n\n", heading, name))
@@ -208,6 +980,15 @@ fn process_item(output: &mut String, item: &Item, data: &Crate, level: usize) {
                     ItemEnum::ProcMacro(_) => {
                         output.push_str(&format!("{} Procedural Macro `{}`\n\n", heading, name))
                     }
+                    // rustdoc puts the crate's original name in `rename` and the
+                    // name it's bound to in `name`
+                    ItemEnum::ExternCrate {
+                        name: binding,
+                        rename: Some(crate_name),
+                    } => output.push_str(&format!(
+                        "{} Extern Crate `{}` as `{}`\n\n",
+                        heading, crate_name, binding
+                    )),
                     ItemEnum::ExternCrate {
                         name: crate_name, ..
                     } => output.push_str(&format!("{} Extern Crate `{}`\n\n", heading, crate_name)),
@@ -243,15 +1024,58 @@ fn process_item(output: &mut String, item: &Item, data: &Crate, level: usize) {
         }
     }
 
-    // Add item attributes if present
-    if !item.attrs.is_empty() {
+    // Turn the heading into a bold term for definition-list style output, keeping
+    // module headings so the crate's structure still shows
+    if options.heading_style == HeadingStyle::DefinitionList
+        && !matches!(item.inner, ItemEnum::Module(_))
+    {
+        let heading_line = output.split_off(heading_start);
+        let term = heading_line.trim_start_matches('#').trim();
+        output.push_str(&format!("**{}**\n\n", term));
+    }
+
+    // Add doc aliases, so items can be found by their alternate names
+    let aliases: Vec<String> = item
+        .attrs
+        .iter()
+        .filter_map(doc_aliases)
+        .flatten()
+        .collect();
+    if !aliases.is_empty() {
+        let aliases: Vec<String> = aliases.iter().map(|alias| format!("`{}`", alias)).collect();
+        output.push_str(&format!("**Aliases:** {}\n\n", aliases.join(", ")));
+    }
+
+    // Add item attributes if present, leaving out those rendered separately
+    let non_exhaustive_note = non_exhaustive_note(item);
+    let attrs: Vec<&Attribute> = item
+        .attrs
+        .iter()
+        .filter(|attr| !(non_exhaustive_note.is_some() && **attr == Attribute::NonExhaustive))
+        .filter(|attr| doc_aliases(attr).is_none())
+        .filter(|attr| !matches!(attr, Attribute::MustUse { .. }))
+        .filter(|attr| stability_note(attr).is_none())
+        .collect();
+    if !attrs.is_empty() {
         output.push_str("**Attributes:**\n\n");
-        for attr in &item.attrs {
+        for attr in attrs {
             output.push_str(&format!("- `{:?}`\n", attr));
         }
         output.push('\n');
     }
 
+    if let Some(note) = non_exhaustive_note {
+        output.push_str(&format!("> {}\n\n", note));
+    }
+
+    if let Some(note) = must_use_note(item) {
+        output.push_str(&format!("> {}\n\n", note));
+    }
+
+    for note in item.attrs.iter().filter_map(stability_note) {
+        output.push_str(&format!("> {}\n\n", note));
+    }
+
     // Add deprecation info if present
     if let Some(deprecation) = &item.deprecation {
         output.push_str("**⚠️ Deprecated");
@@ -268,29 +1092,106 @@ fn process_item(output: &mut String, item: &Item, data: &Crate, level: usize) {
 
     // Add documentation if available
     if let Some(docs) = &item.docs {
-        output.push_str(&format!("{}\n\n", docs));
+        // Modules always get a level 2 heading, regardless of their nesting
+        let docs_level = match item.inner {
+            ItemEnum::Module(_) => 2,
+            _ => heading_level,
+        };
+        output.push_str(&format!("{}\n\n", render_docs(docs, docs_level, options)));
     }
 
     // Add code block with item signature
-    output.push_str("```rust\n");
-    format_item_signature(output, item, data);
-    output.push_str("\n```\n\n");
+    if options.include_signatures {
+        output.push_str("```rust\n");
+        format_item_signature(output, item, data, options);
+        output.push_str("\n```\n\n");
+    }
 
     // Process additional details based on item kind
     match &item.inner {
-        ItemEnum::Module(module) => process_module_details(output, module, data, level + 1),
-        ItemEnum::Struct(struct_) => process_struct_details(output, struct_, data, level + 1),
-        ItemEnum::Enum(enum_) => process_enum_details(output, enum_, data, level + 1),
-        ItemEnum::Union(union_) => process_union_details(output, union_, data, level + 1),
-        ItemEnum::Trait(trait_) => process_trait_details(output, trait_, data, level + 1),
-        ItemEnum::Impl(impl_) => process_impl_details(output, impl_, data, level + 1),
+        ItemEnum::Module(module) => {
+            process_module_details(output, &item.id, module, data, options, level + 1)
+        }
+        ItemEnum::Struct(struct_) => {
+            process_struct_details(output, struct_, data, options, level + 1)
+        }
+        ItemEnum::Enum(enum_) => process_enum_details(output, enum_, data, options, level + 1),
+        ItemEnum::Union(union_) => process_union_details(output, union_, data, options, level + 1),
+        ItemEnum::Trait(trait_) => process_trait_details(output, trait_, data, options, level + 1),
+        ItemEnum::Impl(impl_) => process_impl_details(output, impl_, data, options, level + 1),
+        ItemEnum::ProcMacro(proc_macro) => {
+            process_proc_macro_details(output, proc_macro, level + 1)
+        }
         _ => {}
     }
 }
 
-fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
-    // Format visibility
+/// Formats an item's declaration as Rust source, e.g. `pub fn get(&self) -> u32`,
+/// appending it to `output`. Bodies are elided as `{ /* ... */ }`.
+///
+/// This is the signature shown in the code block under each item. The exact
+/// formatting may change between minor versions as rendering improves, so don't
+/// rely on it being byte-for-byte stable.
+pub fn format_item_signature(
+    output: &mut String,
+    item: &Item,
+    data: &Crate,
+    options: &MarkdownOptions,
+) {
+    // Show foreign items inside the extern block that declares them, so their
+    // ABI reads as part of the FFI contract rather than as a Rust `extern fn`
+    let extern_block = match &item.inner {
+        ItemEnum::Function(function)
+            if !function.has_body
+                && !matches!(function.header.abi, Abi::Rust)
+                && !is_assoc_item(&item.id, data) =>
+        {
+            Some(format_abi(&function.header.abi))
+        }
+        // rustdoc doesn't record the ABI of an extern type's block, so it's left out
+        ItemEnum::ExternType => Some("extern ".to_string()),
+        _ => None,
+    };
+    let Some(extern_block) = extern_block else {
+        format_item_declaration(output, item, data, options);
+        return;
+    };
+
+    let mut declaration = item.clone();
+    if let ItemEnum::Function(function) = &mut declaration.inner {
+        function.header.abi = Abi::Rust;
+    }
+    let mut declaration_signature = String::new();
+    format_item_declaration(&mut declaration_signature, &declaration, data, options);
+    output.push_str(&format!(
+        "{}{{\n    {}\n}}",
+        extern_block,
+        declaration_signature.replace('\n', "\n    ")
+    ));
+}
+
+/// Returns whether an item is declared in a trait or impl block, rather than
+/// directly in a module or extern block.
+fn is_assoc_item(id: &Id, data: &Crate) -> bool {
+    data.index.values().any(|parent| match &parent.inner {
+        ItemEnum::Trait(trait_) => trait_.items.contains(id),
+        ItemEnum::Impl(impl_) => impl_.items.contains(id),
+        _ => false,
+    })
+}
+
+/// Formats an item's signature on its own, without the extern block around
+/// foreign items.
+fn format_item_declaration(
+    output: &mut String,
+    item: &Item,
+    data: &Crate,
+    options: &MarkdownOptions,
+) {
+    // Format visibility, except for proc macros whose attribute comes first and
+    // which write their own `pub fn`
     match &item.visibility {
+        _ if matches!(item.inner, ItemEnum::ProcMacro(_)) => {}
         Visibility::Public => output.push_str("pub "),
         Visibility::Crate => output.push_str("pub(crate) "),
         Visibility::Restricted { path, .. } => output.push_str(&format!("pub(in {}) ", path)),
@@ -312,35 +1213,37 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                 match &struct_.kind {
                     StructKind::Unit => output.push(';'),
                     StructKind::Tuple(fields) => {
-                        output.push('(');
+                        // Collect the fields that can be rendered first, so separators
+                        // only go between fields that actually appear
+                        let mut rendered_fields = Vec::new();
                         for (i, field_opt) in fields.iter().enumerate() {
-                            if let Some(field_id) = field_opt {
-                                if let Some(field_item) = data.index.get(field_id) {
-                                    if let ItemEnum::StructField(field_type) = &field_item.inner {
-                                        // Field visibility if needed
-                                        match &field_item.visibility {
-                                            Visibility::Public => output.push_str("pub "),
-                                            Visibility::Crate => output.push_str("pub(crate) "),
-                                            Visibility::Restricted { path, .. } => {
-                                                output.push_str(&format!("pub(in {}) ", path))
-                                            }
-                                            Visibility::Default => {}
-                                        }
-                                        output.push_str(&format_type(field_type, data));
-                                    }
-                                }
-                                if i < fields.len() - 1 {
-                                    output.push_str(", ");
-                                }
-                            } else {
+                            let Some(field_id) = field_opt else {
                                 // For stripped fields
-                                output.push_str("/* private field */");
-                                if i < fields.len() - 1 {
-                                    output.push_str(", ");
+                                rendered_fields.push(PRIVATE_FIELD.to_string());
+                                continue;
+                            };
+                            let Some(field_item) = data.index.get(field_id) else {
+                                continue;
+                            };
+                            if let ItemEnum::StructField(field_type) = &field_item.inner {
+                                let mut field = String::new();
+                                if options.tuple_field_indices {
+                                    field.push_str(&format!("/* {} */ ", i));
+                                }
+                                // Field visibility if needed
+                                match &field_item.visibility {
+                                    Visibility::Public => field.push_str("pub "),
+                                    Visibility::Crate => field.push_str("pub(crate) "),
+                                    Visibility::Restricted { path, .. } => {
+                                        field.push_str(&format!("pub(in {}) ", path))
+                                    }
+                                    Visibility::Default => {}
                                 }
+                                field.push_str(&format_type(field_type, data));
+                                rendered_fields.push(field);
                             }
                         }
-                        output.push_str(");");
+                        output.push_str(&format!("({});", rendered_fields.join(", ")));
                     }
                     StructKind::Plain {
                         fields,
@@ -370,7 +1273,7 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                             }
                         }
                         if *has_stripped_fields {
-                            output.push_str("    // Some fields omitted\n");
+                            output.push_str(&format!("    {}\n", FIELDS_OMITTED));
                         }
                         output.push('}');
                     }
@@ -392,30 +1295,23 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                                 match &variant.kind {
                                     VariantKind::Plain => {}
                                     VariantKind::Tuple(fields) => {
-                                        output.push('(');
-                                        for (i, field_opt) in fields.iter().enumerate() {
-                                            if let Some(field_id) = field_opt {
-                                                if let Some(field_item) = data.index.get(field_id) {
-                                                    if let ItemEnum::StructField(field_type) =
-                                                        &field_item.inner
-                                                    {
-                                                        output.push_str(&format_type(
-                                                            field_type, data,
-                                                        ));
+                                        let rendered_fields: Vec<String> = fields
+                                            .iter()
+                                            .filter_map(|field_opt| match field_opt {
+                                                Some(field_id) => {
+                                                    match &data.index.get(field_id)?.inner {
+                                                        ItemEnum::StructField(field_type) => {
+                                                            Some(format_type(field_type, data))
+                                                        }
+                                                        _ => None,
                                                     }
                                                 }
-                                                if i < fields.len() - 1 {
-                                                    output.push_str(", ");
-                                                }
-                                            } else {
                                                 // For stripped fields
-                                                output.push_str("/* private field */");
-                                                if i < fields.len() - 1 {
-                                                    output.push_str(", ");
-                                                }
-                                            }
-                                        }
-                                        output.push(')');
+                                                None => Some(PRIVATE_FIELD.to_string()),
+                                            })
+                                            .collect();
+                                        output
+                                            .push_str(&format!("({})", rendered_fields.join(", ")));
                                     }
                                     VariantKind::Struct {
                                         fields,
@@ -438,7 +1334,8 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                                             }
                                         }
                                         if *has_stripped_fields {
-                                            output.push_str("        // Some fields omitted\n");
+                                            output
+                                                .push_str(&format!("        {}\n", FIELDS_OMITTED));
                                         }
                                         output.push_str("    }");
                                     }
@@ -455,7 +1352,7 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                 }
 
                 if enum_.has_stripped_variants {
-                    output.push_str("    // Some variants omitted\n");
+                    output.push_str(&format!("    {}\n", VARIANTS_OMITTED));
                 }
 
                 output.push('}');
@@ -490,87 +1387,26 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                 }
 
                 if union_.has_stripped_fields {
-                    output.push_str("    // Some fields omitted\n");
+                    output.push_str(&format!("    {}\n", FIELDS_OMITTED));
                 }
 
                 output.push('}');
             }
         }
         ItemEnum::Function(function) => {
-            // Function header
+            // Function header, in the order Rust requires: `const async unsafe`
             if function.header.is_const {
                 output.push_str("const ");
             }
-            if function.header.is_unsafe {
-                output.push_str("unsafe ");
-            }
             if function.header.is_async {
                 output.push_str("async ");
             }
+            if function.header.is_unsafe {
+                output.push_str("unsafe ");
+            }
 
             // ABI
-            match &function.header.abi {
-                Abi::Rust => {}
-                Abi::C { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"C-unwind\" ");
-                    } else {
-                        output.push_str("extern \"C\" ");
-                    }
-                }
-                Abi::Cdecl { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"cdecl-unwind\" ");
-                    } else {
-                        output.push_str("extern \"cdecl\" ");
-                    }
-                }
-                Abi::Stdcall { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"stdcall-unwind\" ");
-                    } else {
-                        output.push_str("extern \"stdcall\" ");
-                    }
-                }
-                Abi::Fastcall { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"fastcall-unwind\" ");
-                    } else {
-                        output.push_str("extern \"fastcall\" ");
-                    }
-                }
-                Abi::Aapcs { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"aapcs-unwind\" ");
-                    } else {
-                        output.push_str("extern \"aapcs\" ");
-                    }
-                }
-                Abi::Win64 { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"win64-unwind\" ");
-                    } else {
-                        output.push_str("extern \"win64\" ");
-                    }
-                }
-                Abi::SysV64 { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"sysv64-unwind\" ");
-                    } else {
-                        output.push_str("extern \"sysv64\" ");
-                    }
-                }
-                Abi::System { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"system-unwind\" ");
-                    } else {
-                        output.push_str("extern \"system\" ");
-                    }
-                }
-                Abi::Other(abi) => {
-                    output.push_str(&format!("extern \"{}\" ", abi));
-                }
-            }
+            output.push_str(&format_abi(&function.header.abi));
 
             // Function name
             if let Some(name) = &item.name {
@@ -582,11 +1418,17 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                 // Parameters
                 output.push('(');
                 for (i, (param_name, param_type)) in function.sig.inputs.iter().enumerate() {
-                    output.push_str(&format!(
-                        "{}: {}",
-                        param_name,
-                        format_type(param_type, data)
-                    ));
+                    if let Some(receiver) = format_receiver(param_name, param_type, data) {
+                        output.push_str(&receiver);
+                    } else if options.elide_param_names {
+                        output.push_str(&format_type(param_type, data));
+                    } else {
+                        output.push_str(&format!(
+                            "{}: {}",
+                            param_name,
+                            format_type(param_type, data)
+                        ));
+                    }
                     if i < function.sig.inputs.len() - 1 || function.sig.is_c_variadic {
                         output.push_str(", ");
                     }
@@ -599,8 +1441,15 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
 
                 output.push(')');
 
-                // Return type
-                if let Some(return_type) = &function.sig.output {
+                // Return type, written as the `async fn` sugar's return type if the
+                // JSON has the desugared `impl Future<Output = T>`
+                let return_type = match &function.sig.output {
+                    Some(return_type) if function.header.is_async => {
+                        async_fn_output(return_type).unwrap_or(Some(return_type))
+                    }
+                    return_type => return_type.as_ref(),
+                };
+                if let Some(return_type) = return_type {
                     output.push_str(&format!(" -> {}", format_type(return_type, data)));
                 }
 
@@ -652,39 +1501,7 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
             }
         }
         ItemEnum::Impl(impl_) => {
-            // Impl modifiers
-            if impl_.is_unsafe {
-                output.push_str("unsafe ");
-            }
-
-            output.push_str("impl");
-
-            // Generics
-            format_generics(output, &impl_.generics, data);
-
-            // Trait reference if this is a trait impl
-            if let Some(trait_) = &impl_.trait_ {
-                if impl_.is_negative {
-                    output.push_str(" !");
-                } else {
-                    output.push(' ');
-                }
-
-                output.push_str(&trait_.path);
-                if let Some(args) = &trait_.args {
-                    let mut args_str = String::new();
-                    format_generic_args(&mut args_str, args, data);
-                    output.push_str(&args_str);
-                }
-
-                output.push_str(" for ");
-            }
-
-            // For type
-            output.push_str(&format_type(&impl_.for_, data));
-
-            // Where clause
-            format_where_clause(output, &impl_.generics.where_predicates, data);
+            format_impl_header(output, impl_, data);
 
             output.push_str(" {\n    /* Associated items */\n}");
 
@@ -744,16 +1561,15 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
 
                     MacroKind::Attr => output.push_str("_attribute]"),
                     MacroKind::Derive => {
-                        output.push_str("_derive]");
+                        // Written the way the derive is declared, with its helpers
+                        output.push_str(&format!("_derive({}", name));
                         if !proc_macro.helpers.is_empty() {
-                            output.push_str("\n// Helpers: ");
-                            for (i, helper) in proc_macro.helpers.iter().enumerate() {
-                                output.push_str(&format!("#[{}]", helper));
-                                if i < proc_macro.helpers.len() - 1 {
-                                    output.push_str(", ");
-                                }
-                            }
+                            output.push_str(&format!(
+                                ", attributes({})",
+                                proc_macro.helpers.join(", ")
+                            ));
                         }
+                        output.push_str(")]");
                     }
                 }
                 output.push_str(&format!(
@@ -762,13 +1578,13 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                 ));
             }
         }
-        ItemEnum::ExternCrate { name, rename } => {
-            output.push_str(&format!("extern crate {}", name));
-            if let Some(rename_val) = rename {
-                output.push_str(&format!(" as {}", rename_val));
+        ItemEnum::ExternCrate { name, rename } => match rename {
+            // `rename` holds the crate's original name, as in the heading
+            Some(crate_name) => {
+                output.push_str(&format!("extern crate {} as {};", crate_name, name))
             }
-            output.push(';');
-        }
+            None => output.push_str(&format!("extern crate {};", name)),
+        },
         ItemEnum::Use(use_item) => {
             output.push_str(&format!("use {}", use_item.source));
             if use_item.is_glob {
@@ -787,14 +1603,6 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
         ItemEnum::StructField(field_type) => {
             // For struct fields, just output the type
             if let Some(name) = &item.name {
-                match &item.visibility {
-                    Visibility::Public => output.push_str("pub "),
-                    Visibility::Crate => output.push_str("pub(crate) "),
-                    Visibility::Restricted { path, .. } => {
-                        output.push_str(&format!("pub(in {}) ", path))
-                    }
-                    Visibility::Default => {}
-                }
                 output.push_str(&format!("{}: {}", name, format_type(field_type, data)));
             } else {
                 output.push_str(&format_type(field_type, data));
@@ -821,7 +1629,7 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                                 }
                             } else {
                                 // For stripped fields
-                                output.push_str("/* private field */");
+                                output.push_str(PRIVATE_FIELD);
                                 if i < fields.len() - 1 {
                                     output.push_str(", ");
                                 }
@@ -848,7 +1656,7 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
                             }
                         }
                         if *has_stripped_fields {
-                            output.push_str("    // Some fields omitted\n");
+                            output.push_str(&format!("    {}\n", FIELDS_OMITTED));
                         }
                         output.push('}');
                     }
@@ -864,7 +1672,7 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
         }
         ItemEnum::ExternType => {
             if let Some(name) = &item.name {
-                output.push_str(&format!("extern {{ type {}; }}", name));
+                output.push_str(&format!("type {};", name));
             }
         }
         ItemEnum::AssocConst { type_, value } => {
@@ -901,22 +1709,93 @@ fn format_item_signature(output: &mut String, item: &Item, data: &Crate) {
     }
 }
 
-fn format_generics(output: &mut String, generics: &Generics, data: &Crate) {
-    if generics.params.is_empty() {
-        return;
+/// Formats an impl's header, from `impl` up to and including its where clause,
+/// without the body.
+fn format_impl_header(output: &mut String, impl_: &Impl, data: &Crate) {
+    // Impl modifiers
+    if impl_.is_unsafe {
+        output.push_str("unsafe ");
+    }
+
+    output.push_str("impl");
+
+    // Generics
+    format_generics(output, &impl_.generics, data);
+
+    // Trait reference if this is a trait impl
+    if let Some(trait_) = &impl_.trait_ {
+        if impl_.is_negative {
+            output.push_str(" !");
+        } else {
+            output.push(' ');
+        }
+
+        output.push_str(&trait_.path);
+        if let Some(args) = &trait_.args {
+            let mut args_str = String::new();
+            format_generic_args(&mut args_str, args, data);
+            output.push_str(&args_str);
+        }
+
+        output.push_str(" for ");
+    } else {
+        output.push(' ');
+    }
+
+    // For type
+    output.push_str(&format_type(&impl_.for_, data));
+
+    // Where clause
+    format_where_clause(output, &impl_.generics.where_predicates, data);
+}
+
+/// Formats a `self` parameter the way it's written in source, e.g. `&mut self`
+/// rather than `self: &mut Self`. Returns `None` for any other parameter.
+fn format_receiver(param_name: &str, param_type: &Type, data: &Crate) -> Option<String> {
+    if param_name != "self" {
+        return None;
     }
 
-    output.push('<');
-    for (i, param) in generics.params.iter().enumerate() {
+    let receiver = match param_type {
+        Type::Generic(name) if name == "Self" => "self".to_string(),
+        Type::BorrowedRef {
+            lifetime,
+            is_mutable,
+            type_,
+        } if matches!(type_.as_ref(), Type::Generic(name) if name == "Self") => {
+            let mut receiver = String::from("&");
+            if let Some(lt) = lifetime {
+                receiver.push_str(&format!("{} ", format_lifetime(lt)));
+            }
+            if *is_mutable {
+                receiver.push_str("mut ");
+            }
+            receiver.push_str("self");
+            receiver
+        }
+        _ => format!("self: {}", format_type(param_type, data)),
+    };
+
+    Some(receiver)
+}
+
+fn format_generics(output: &mut String, generics: &Generics, data: &Crate) {
+    let mut rendered_params = Vec::new();
+    for param in &generics.params {
+        let mut rendered = String::new();
         match &param.kind {
             GenericParamDefKind::Lifetime { outlives } => {
-                output.push_str(&format!("'{}", param.name));
+                // Elided lifetimes (`'_`) can't be declared
+                if param.name.trim_start_matches('\'') == "_" {
+                    continue;
+                }
+                rendered.push_str(&format_lifetime(&param.name));
                 if !outlives.is_empty() {
-                    output.push_str(": ");
+                    rendered.push_str(": ");
                     for (j, lifetime) in outlives.iter().enumerate() {
-                        output.push_str(&format!("'{}", lifetime));
+                        rendered.push_str(&format_lifetime(lifetime));
                         if j < outlives.len() - 1 {
-                            output.push_str(" + ");
+                            rendered.push_str(" + ");
                         }
                     }
                 }
@@ -926,37 +1805,39 @@ fn format_generics(output: &mut String, generics: &Generics, data: &Crate) {
                 default,
                 is_synthetic,
             } => {
-                // If synthetic, add a note
+                // Synthetic params come from `impl Trait` arguments, which are
+                // written in the parameter list instead
                 if *is_synthetic {
-                    output.push_str("/* synthetic */ ");
+                    continue;
                 }
 
-                output.push_str(&param.name);
+                rendered.push_str(&param.name);
                 if !bounds.is_empty() {
-                    output.push_str(": ");
-                    format_bounds(output, bounds, data);
+                    rendered.push_str(": ");
+                    format_bounds(&mut rendered, bounds, data);
                 }
                 if let Some(default_type) = default {
-                    output.push_str(&format!(" = {}", format_type(default_type, data)));
+                    rendered.push_str(&format!(" = {}", format_type(default_type, data)));
                 }
             }
             GenericParamDefKind::Const { type_, default } => {
-                output.push_str(&format!(
+                rendered.push_str(&format!(
                     "const {}: {}",
                     param.name,
                     format_type(type_, data)
                 ));
                 if let Some(default_value) = default {
-                    output.push_str(&format!(" = {}", default_value));
+                    rendered.push_str(&format!(" = {}", format_const_arg(default_value)));
                 }
             }
         }
+        rendered_params.push(rendered);
+    }
 
-        if i < generics.params.len() - 1 {
-            output.push_str(", ");
-        }
+    // Leave out the brackets entirely rather than emitting a bare `<>`
+    if !rendered_params.is_empty() {
+        output.push_str(&format!("<{}>", rendered_params.join(", ")));
     }
-    output.push('>');
 }
 
 fn format_where_clause(output: &mut String, predicates: &[WherePredicate], data: &Crate) {
@@ -964,64 +1845,64 @@ fn format_where_clause(output: &mut String, predicates: &[WherePredicate], data:
         return;
     }
 
-    output.push_str("\nwhere\n    ");
-    for (i, predicate) in predicates.iter().enumerate() {
-        match predicate {
-            WherePredicate::BoundPredicate {
-                type_,
-                bounds,
-                generic_params,
-            } => {
-                if !generic_params.is_empty() {
-                    output.push_str("for<");
-                    for (j, param) in generic_params.iter().enumerate() {
-                        match &param.kind {
-                            GenericParamDefKind::Lifetime { .. } => {
-                                output.push_str(&format!("'{}", param.name));
-                            }
-                            _ => output.push_str(&param.name),
-                        }
-
-                        if j < generic_params.len() - 1 {
-                            output.push_str(", ");
-                        }
+    // Sort a rendered snapshot of the predicates, as rustdoc doesn't keep their
+    // order stable for synthetic bounds
+    let mut rendered_predicates: Vec<String> = predicates
+        .iter()
+        .map(|predicate| {
+            let mut rendered = String::new();
+            match predicate {
+                WherePredicate::BoundPredicate {
+                    type_,
+                    bounds,
+                    generic_params,
+                } => {
+                    rendered.push_str(&format_for_binder(generic_params));
+
+                    rendered.push_str(&format_type(type_, data));
+
+                    if !bounds.is_empty() {
+                        rendered.push_str(": ");
+                        format_bounds(&mut rendered, bounds, data);
                     }
-                    output.push_str("> ");
-                }
-
-                output.push_str(&format_type(type_, data));
-
-                if !bounds.is_empty() {
-                    output.push_str(": ");
-                    format_bounds(output, bounds, data);
                 }
-            }
-            WherePredicate::LifetimePredicate { lifetime, outlives } => {
-                output.push_str(&format!("'{}", lifetime));
-                if !outlives.is_empty() {
-                    output.push_str(": ");
-                    for (j, outlive) in outlives.iter().enumerate() {
-                        output.push_str(&format!("'{}", outlive));
-                        if j < outlives.len() - 1 {
-                            output.push_str(" + ");
+                WherePredicate::LifetimePredicate { lifetime, outlives } => {
+                    rendered.push_str(&format_lifetime(lifetime));
+                    if !outlives.is_empty() {
+                        rendered.push_str(": ");
+                        for (j, outlive) in outlives.iter().enumerate() {
+                            rendered.push_str(&format_lifetime(outlive));
+                            if j < outlives.len() - 1 {
+                                rendered.push_str(" + ");
+                            }
                         }
                     }
                 }
-            }
-            WherePredicate::EqPredicate { lhs, rhs } => {
-                output.push_str(&format_type(lhs, data));
-                output.push_str(" = ");
-                match rhs {
-                    Term::Type(type_) => output.push_str(&format_type(type_, data)),
-                    Term::Constant(constant) => output.push_str(&constant.expr),
+                WherePredicate::EqPredicate { lhs, rhs } => {
+                    rendered.push_str(&format_type(lhs, data));
+                    rendered.push_str(" = ");
+                    match rhs {
+                        Term::Type(type_) => rendered.push_str(&format_type(type_, data)),
+                        Term::Constant(constant) => rendered.push_str(&constant.expr),
+                    }
                 }
             }
-        }
-
-        if i < predicates.len() - 1 {
-            output.push_str(",\n    ");
-        }
+            rendered
+        })
+        .collect();
+    rendered_predicates.sort();
+
+    // Keep a single predicate on the same line if it fits, e.g. `where T: Clone`
+    let line_len = output.rsplit('\n').next().unwrap_or("").len();
+    if let [predicate] = rendered_predicates.as_slice()
+        && line_len + " where ".len() + predicate.len() <= INLINE_WHERE_CLAUSE_MAX_WIDTH
+    {
+        output.push_str(&format!(" where {}", predicate));
+        return;
     }
+
+    output.push_str("\nwhere\n    ");
+    output.push_str(&rendered_predicates.join(",\n    "));
 }
 
 fn format_bounds(output: &mut String, bounds: &[GenericBound], data: &Crate) {
@@ -1038,22 +1919,7 @@ fn format_bounds(output: &mut String, bounds: &[GenericBound], data: &Crate) {
                     TraitBoundModifier::MaybeConst => output.push_str("~const "),
                 }
 
-                if !generic_params.is_empty() {
-                    output.push_str("for<");
-                    for (j, param) in generic_params.iter().enumerate() {
-                        match &param.kind {
-                            GenericParamDefKind::Lifetime { .. } => {
-                                output.push_str(&format!("'{}", param.name));
-                            }
-                            _ => output.push_str(&param.name),
-                        }
-
-                        if j < generic_params.len() - 1 {
-                            output.push_str(", ");
-                        }
-                    }
-                    output.push_str("> ");
-                }
+                output.push_str(&format_for_binder(generic_params));
 
                 output.push_str(&trait_.path);
                 if let Some(args) = &trait_.args {
@@ -1063,14 +1929,14 @@ fn format_bounds(output: &mut String, bounds: &[GenericBound], data: &Crate) {
                 }
             }
             GenericBound::Outlives(lifetime) => {
-                output.push_str(&format!("'{}", lifetime));
+                output.push_str(&format_lifetime(lifetime));
             }
             GenericBound::Use(args) => {
                 output.push_str("use<");
                 for (i, arg) in args.iter().enumerate() {
                     match arg {
                         PreciseCapturingArg::Lifetime(lifetime) => {
-                            output.push_str(&format!("'{}", lifetime))
+                            output.push_str(&format_lifetime(lifetime))
                         }
                         PreciseCapturingArg::Param(param) => output.push_str(param),
                     }
@@ -1089,6 +1955,22 @@ fn format_bounds(output: &mut String, bounds: &[GenericBound], data: &Crate) {
     }
 }
 
+/// Formats a const generic argument the way it has to be written in source: bare
+/// when it's a literal or a path like `N`, and in braces otherwise, e.g. `{ N * 2 }`.
+fn format_const_arg(expr: &str) -> String {
+    let expr = expr.trim();
+    let is_literal_or_path = expr
+        .strip_prefix('-')
+        .unwrap_or(expr)
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '.' | '\'' | '"'));
+    if expr.starts_with('{') || is_literal_or_path {
+        expr.to_string()
+    } else {
+        format!("{{ {} }}", expr)
+    }
+}
+
 fn format_generic_args(output: &mut String, args: &GenericArgs, data: &Crate) {
     match args {
         GenericArgs::AngleBracketed { args, constraints } => {
@@ -1101,9 +1983,11 @@ fn format_generic_args(output: &mut String, args: &GenericArgs, data: &Crate) {
             // Format args
             for (i, arg) in args.iter().enumerate() {
                 match arg {
-                    GenericArg::Lifetime(lifetime) => output.push_str(&format!("'{}", lifetime)),
+                    GenericArg::Lifetime(lifetime) => output.push_str(&format_lifetime(lifetime)),
                     GenericArg::Type(type_) => output.push_str(&format_type(type_, data)),
-                    GenericArg::Const(constant) => output.push_str(&constant.expr),
+                    GenericArg::Const(constant) => {
+                        output.push_str(&format_const_arg(&constant.expr))
+                    }
                     GenericArg::Infer => output.push('_'),
                 }
 
@@ -1130,7 +2014,9 @@ fn format_generic_args(output: &mut String, args: &GenericArgs, data: &Crate) {
                         output.push_str(" = ");
                         match term {
                             Term::Type(type_) => output.push_str(&format_type(type_, data)),
-                            Term::Constant(constant) => output.push_str(&constant.expr),
+                            Term::Constant(constant) => {
+                                output.push_str(&format_const_arg(&constant.expr))
+                            }
                         }
                     }
                     AssocItemConstraintKind::Constraint(bounds) => {
@@ -1166,12 +2052,143 @@ fn format_generic_args(output: &mut String, args: &GenericArgs, data: &Crate) {
             }
         }
         GenericArgs::ReturnTypeNotation => {
-            output.push_str("::method(..)");
+            // The args follow the method name, which the constraint or qualified
+            // path has already written, giving `method(..): Send` or `T::method(..)`
+            output.push_str("(..)");
+        }
+    }
+}
+
+/// Formats a lifetime with a single leading `'`, whether or not the JSON includes it.
+fn format_lifetime(lifetime: &str) -> String {
+    format!("'{}", lifetime.trim_start_matches('\''))
+}
+
+/// Formats the `for<'a, 'b> ` binder of a higher-ranked bound or function pointer,
+/// including its trailing space, or an empty string if there are no params.
+fn format_for_binder(generic_params: &[GenericParamDef]) -> String {
+    if generic_params.is_empty() {
+        return String::new();
+    }
+
+    let params: Vec<String> = generic_params
+        .iter()
+        .map(|param| match &param.kind {
+            GenericParamDefKind::Lifetime { .. } => format_lifetime(&param.name),
+            _ => param.name.clone(),
+        })
+        .collect();
+    format!("for<{}> ", params.join(", "))
+}
+
+/// Formats an ABI as the `extern "abi" ` prefix of a function or extern block, or
+/// an empty string for the Rust ABI.
+fn format_abi(abi: &Abi) -> String {
+    let (name, unwind) = match abi {
+        Abi::Rust => return String::new(),
+        Abi::C { unwind } => ("C", *unwind),
+        Abi::Cdecl { unwind } => ("cdecl", *unwind),
+        Abi::Stdcall { unwind } => ("stdcall", *unwind),
+        Abi::Fastcall { unwind } => ("fastcall", *unwind),
+        Abi::Aapcs { unwind } => ("aapcs", *unwind),
+        Abi::Win64 { unwind } => ("win64", *unwind),
+        Abi::SysV64 { unwind } => ("sysv64", *unwind),
+        Abi::System { unwind } => ("system", *unwind),
+        Abi::Other(abi) => return format!("extern \"{}\" ", abi),
+    };
+
+    if unwind {
+        format!("extern \"{}-unwind\" ", name)
+    } else {
+        format!("extern \"{}\" ", name)
+    }
+}
+
+/// Formats the type behind a reference or pointer, wrapping `dyn`/`impl` types with
+/// more than one bound in parentheses, e.g. `&(dyn FnMut() + Send)`, as `+` would
+/// otherwise bind to the reference.
+fn format_pointee(ty: &Type, data: &Crate) -> String {
+    let has_multiple_bounds = match ty {
+        Type::DynTrait(dyn_trait) => {
+            dyn_trait.traits.len() + usize::from(dyn_trait.lifetime.is_some()) > 1
+        }
+        Type::ImplTrait(bounds) => bounds.len() > 1,
+        _ => false,
+    };
+
+    if has_multiple_bounds {
+        format!("({})", format_type(ty, data))
+    } else {
+        format_type(ty, data)
+    }
+}
+
+/// Auto traits, which are listed after the primary trait of an `impl Trait`.
+const AUTO_TRAITS: &[&str] = &["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+/// If `ty` is a desugared `async fn` return type, `impl Future<Output = T>`, returns
+/// the `T` it was written with, or `Some(None)` when `T` is `()`.
+fn async_fn_output(ty: &Type) -> Option<Option<&Type>> {
+    let Type::ImplTrait(bounds) = ty else {
+        return None;
+    };
+
+    // Other than the `Future` bound, only captured lifetimes are expected
+    let mut future_output = None;
+    for bound in bounds {
+        match bound {
+            GenericBound::TraitBound { trait_, .. }
+                if future_output.is_none()
+                    && (trait_.path == "Future" || trait_.path.ends_with("::Future")) =>
+            {
+                let Some(GenericArgs::AngleBracketed { constraints, .. }) = trait_.args.as_deref()
+                else {
+                    return None;
+                };
+                future_output =
+                    constraints
+                        .iter()
+                        .find_map(|constraint| match &constraint.binding {
+                            AssocItemConstraintKind::Equality(Term::Type(output_type))
+                                if constraint.name == "Output" =>
+                            {
+                                Some(output_type)
+                            }
+                            _ => None,
+                        });
+                future_output?;
+            }
+            GenericBound::Outlives(_) | GenericBound::Use(_) => {}
+            _ => return None,
+        }
+    }
+
+    let output_type = future_output?;
+    match output_type {
+        Type::Tuple(types) if types.is_empty() => Some(None),
+        _ => Some(Some(output_type)),
+    }
+}
+
+/// Orders the bounds of an `impl Trait`: regular traits, auto traits, lifetimes,
+/// then `use<..>` capture lists.
+fn impl_trait_bound_rank(bound: &GenericBound) -> u8 {
+    match bound {
+        GenericBound::TraitBound { trait_, .. } => {
+            let name = trait_.path.rsplit("::").next().unwrap_or(&trait_.path);
+            u8::from(AUTO_TRAITS.contains(&name))
         }
+        GenericBound::Outlives(_) => 2,
+        GenericBound::Use(_) => 3,
     }
 }
 
-fn format_type(ty: &Type, data: &Crate) -> String {
+/// Formats a type as Rust source, e.g. `Option<&'a str>`, using `data` to resolve
+/// paths.
+///
+/// As with [`format_item_signature`], the output is meant for display and its
+/// exact formatting may change between minor versions.
+pub fn format_type(ty: &Type, data: &Crate) -> String {
     let mut output = String::new();
 
     match ty {
@@ -1188,22 +2205,7 @@ fn format_type(ty: &Type, data: &Crate) -> String {
 
             for (i, trait_) in dyn_trait.traits.iter().enumerate() {
                 // Higher-rank bounds if necessary
-                if !trait_.generic_params.is_empty() {
-                    output.push_str("for<");
-                    for (j, param) in trait_.generic_params.iter().enumerate() {
-                        match &param.kind {
-                            GenericParamDefKind::Lifetime { .. } => {
-                                output.push_str(&format!("'{}", param.name));
-                            }
-                            _ => output.push_str(&param.name),
-                        }
-
-                        if j < trait_.generic_params.len() - 1 {
-                            output.push_str(", ");
-                        }
-                    }
-                    output.push_str("> ");
-                }
+                output.push_str(&format_for_binder(&trait_.generic_params));
 
                 output.push_str(&trait_.trait_.path);
                 if let Some(args) = &trait_.trait_.args {
@@ -1219,7 +2221,7 @@ fn format_type(ty: &Type, data: &Crate) -> String {
 
             // Lifetime bound if present
             if let Some(lifetime) = &dyn_trait.lifetime {
-                output.push_str(&format!(" + '{}", lifetime));
+                output.push_str(&format!(" + {}", format_lifetime(lifetime)));
             }
         }
         Type::Generic(name) => {
@@ -1230,22 +2232,7 @@ fn format_type(ty: &Type, data: &Crate) -> String {
         }
         Type::FunctionPointer(fn_ptr) => {
             // For clarity about the parameters
-            if !fn_ptr.generic_params.is_empty() {
-                output.push_str("for<");
-                for (j, param) in fn_ptr.generic_params.iter().enumerate() {
-                    match &param.kind {
-                        GenericParamDefKind::Lifetime { .. } => {
-                            output.push_str(&format!("'{}", param.name));
-                        }
-                        _ => output.push_str(&param.name),
-                    }
-
-                    if j < fn_ptr.generic_params.len() - 1 {
-                        output.push_str(", ");
-                    }
-                }
-                output.push_str("> ");
-            }
+            output.push_str(&format_for_binder(&fn_ptr.generic_params));
 
             // Function header (const, unsafe, extern, etc.)
             if fn_ptr.header.is_const {
@@ -1256,68 +2243,7 @@ fn format_type(ty: &Type, data: &Crate) -> String {
             }
 
             // ABI
-            match &fn_ptr.header.abi {
-                Abi::Rust => {}
-                Abi::C { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"C-unwind\" ");
-                    } else {
-                        output.push_str("extern \"C\" ");
-                    }
-                }
-                Abi::Cdecl { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"cdecl-unwind\" ");
-                    } else {
-                        output.push_str("extern \"cdecl\" ");
-                    }
-                }
-                Abi::Stdcall { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"stdcall-unwind\" ");
-                    } else {
-                        output.push_str("extern \"stdcall\" ");
-                    }
-                }
-                Abi::Fastcall { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"fastcall-unwind\" ");
-                    } else {
-                        output.push_str("extern \"fastcall\" ");
-                    }
-                }
-                Abi::Aapcs { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"aapcs-unwind\" ");
-                    } else {
-                        output.push_str("extern \"aapcs\" ");
-                    }
-                }
-                Abi::Win64 { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"win64-unwind\" ");
-                    } else {
-                        output.push_str("extern \"win64\" ");
-                    }
-                }
-                Abi::SysV64 { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"sysv64-unwind\" ");
-                    } else {
-                        output.push_str("extern \"sysv64\" ");
-                    }
-                }
-                Abi::System { unwind } => {
-                    if *unwind {
-                        output.push_str("extern \"system-unwind\" ");
-                    } else {
-                        output.push_str("extern \"system\" ");
-                    }
-                }
-                Abi::Other(abi) => {
-                    output.push_str(&format!("extern \"{}\" ", abi));
-                }
-            }
+            output.push_str(&format_abi(&fn_ptr.header.abi));
 
             output.push_str("fn(");
 
@@ -1365,8 +2291,10 @@ fn format_type(ty: &Type, data: &Crate) -> String {
             type_,
             __pat_unstable_do_not_use,
         } => {
+            // Pattern types are unstable, so keep them in a comment rather than
+            // passing them off as stable syntax
             output.push_str(&format!(
-                "{} is {}",
+                "/* pattern type: {} is {} */",
                 format_type(type_, data),
                 __pat_unstable_do_not_use
             ));
@@ -1374,8 +2302,13 @@ fn format_type(ty: &Type, data: &Crate) -> String {
         Type::ImplTrait(bounds) => {
             output.push_str("impl ");
 
+            // Put the primary trait first, then auto traits, then lifetimes, so
+            // e.g. `impl Future<Output = T> + Send + 'a` reads the same every time
+            let mut ordered_bounds = bounds.clone();
+            ordered_bounds.sort_by_key(impl_trait_bound_rank);
+
             let mut bounds_str = String::new();
-            format_bounds(&mut bounds_str, bounds, data);
+            format_bounds(&mut bounds_str, &ordered_bounds, data);
             output.push_str(&bounds_str);
         }
         Type::Infer => {
@@ -1387,7 +2320,7 @@ fn format_type(ty: &Type, data: &Crate) -> String {
             } else {
                 output.push_str("*const ");
             }
-            output.push_str(&format_type(type_, data));
+            output.push_str(&format_pointee(type_, data));
         }
         Type::BorrowedRef {
             lifetime,
@@ -1396,12 +2329,12 @@ fn format_type(ty: &Type, data: &Crate) -> String {
         } => {
             output.push('&');
             if let Some(lt) = lifetime {
-                output.push_str(&format!("'{} ", lt));
+                output.push_str(&format!("{} ", format_lifetime(lt)));
             }
             if *is_mutable {
                 output.push_str("mut ");
             }
-            output.push_str(&format_type(type_, data));
+            output.push_str(&format_pointee(type_, data));
         }
         Type::QualifiedPath {
             name,
@@ -1409,19 +2342,43 @@ fn format_type(ty: &Type, data: &Crate) -> String {
             self_type,
             trait_,
         } => {
-            output.push('<');
-            output.push_str(&format_type(self_type, data));
+            let is_self = matches!(self_type.as_ref(), Type::Generic(generic) if generic == "Self");
+            // rustdoc leaves the trait's path empty when the source didn't name it,
+            // as in `T::Item`
+            let trait_ = trait_
+                .as_ref()
+                .filter(|trait_path| !trait_path.path.is_empty());
+            // `Self::Output` can't say which instantiation of a generic trait is meant,
+            // so keep the full form when the trait has args, e.g. `<Self as Tr<u8>>::Out`
+            let trait_has_args = trait_.is_some_and(|trait_path| {
+                let mut trait_args = String::new();
+                if let Some(args) = &trait_path.args {
+                    format_generic_args(&mut trait_args, args, data);
+                }
+                !trait_args.is_empty()
+            });
 
-            if let Some(trait_path) = trait_ {
-                output.push_str(&format!(" as {}", trait_path.path));
-                if let Some(trait_args) = &trait_path.args {
-                    let mut args_str = String::new();
-                    format_generic_args(&mut args_str, trait_args, data);
-                    output.push_str(&args_str);
+            if (is_self && !trait_has_args)
+                || (trait_.is_none() && matches!(self_type.as_ref(), Type::Generic(_)))
+            {
+                // Write projections on `Self` or on a bare generic the way they're
+                // usually written in source, e.g. `Self::Output` or `T::Item`
+                output.push_str(&format!("{}::{}", format_type(self_type, data), name));
+            } else {
+                output.push('<');
+                output.push_str(&format_type(self_type, data));
+
+                if let Some(trait_path) = trait_ {
+                    output.push_str(&format!(" as {}", trait_path.path));
+                    if let Some(trait_args) = &trait_path.args {
+                        let mut args_str = String::new();
+                        format_generic_args(&mut args_str, trait_args, data);
+                        output.push_str(&args_str);
+                    }
                 }
-            }
 
-            output.push_str(&format!(">::{}", name));
+                output.push_str(&format!(">::{}", name));
+            }
 
             if let Some(args) = args {
                 let mut args_str = String::new();
@@ -1436,73 +2393,429 @@ fn format_type(ty: &Type, data: &Crate) -> String {
     output
 }
 
-fn process_module_details(output: &mut String, module: &Module, data: &Crate, _level: usize) {
-    if module.is_stripped {
-        output.push_str(
-            "> **Note:** This module is marked as stripped. Some items may be omitted.\n\n",
-        );
+/// Renders an item's doc comment, applying the doc-level options.
+///
+/// The docs are walked line by line while tracking fenced code blocks, so that
+/// transformations never touch the contents of a fence they don't target.
+fn render_docs(docs: &str, heading_level: usize, options: &MarkdownOptions) -> String {
+    let mut lines = Vec::new();
+    // The open fence's character and length, and whether it holds Rust code
+    let mut open_fence: Option<(char, usize, bool)> = None;
+
+    for line in docs.lines() {
+        match open_fence {
+            None => {
+                if let Some((fence_char, fence_len, info)) = parse_code_fence(line) {
+                    let language = info.split([',', ' ']).next().unwrap_or("");
+                    if options.diagram_blocks == DiagramBlocks::Label
+                        && DIAGRAM_LANGUAGES.contains(&language)
+                    {
+                        lines.push(format!("*Diagram ({}):*", language));
+                        lines.push(String::new());
+                    }
+                    open_fence = Some((fence_char, fence_len, is_rust_code_block(info)));
+                } else {
+                    let line =
+                        nest_heading(line, heading_level).unwrap_or_else(|| line.to_string());
+                    lines.push(
+                        match options
+                            .doc_asset_base_url
+                            .as_deref()
+                            .filter(|base_url| !base_url.is_empty())
+                        {
+                            Some(base_url) => rebase_relative_urls(&line, base_url),
+                            None => line,
+                        },
+                    );
+                    continue;
+                }
+            }
+            Some((fence_char, fence_len, is_rust)) => {
+                if let Some((close_char, close_len, info)) = parse_code_fence(line)
+                    && close_char == fence_char
+                    && close_len >= fence_len
+                    && info.is_empty()
+                {
+                    open_fence = None;
+                } else if is_rust && options.strip_doc_tests {
+                    // Drop doctest lines rustdoc hides, and unescape `##` lines
+                    let trimmed = line.trim_start();
+                    if trimmed == "#" || trimmed.starts_with("# ") {
+                        continue;
+                    }
+                    if trimmed.starts_with("##") {
+                        let indent = &line[..line.len() - trimmed.len()];
+                        lines.push(format!("{}{}", indent, &trimmed[1..]));
+                        continue;
+                    }
+                }
+            }
+        }
+
+        lines.push(line.to_string());
     }
 
-    // Reset level when entering a module to avoid excessive nesting
-    // This ensures that module contents are always at a reasonable heading level
-    process_items(output, &module.items, data, 3);
+    lines.join("\n")
 }
 
-fn process_struct_details(output: &mut String, struct_: &Struct, data: &Crate, level: usize) {
-    // Cap heading level at 6 (maximum valid Markdown heading level)
-    let heading_level = std::cmp::min(level, 6);
-    // Detail fields based on struct kind
-    match &struct_.kind {
-        StructKind::Unit => {
-            // Nothing to detail for unit structs
+/// Resolves the relative URLs of inline links, images and reference definitions
+/// in a line of docs against `base_url`, leaving absolute URLs, anchors, intra-doc
+/// links and code spans alone.
+fn rebase_relative_urls(line: &str, base_url: &str) -> String {
+    let rebase = |url: &str| {
+        if is_relative_asset_url(url) {
+            format!(
+                "{}/{}",
+                base_url.trim_end_matches('/'),
+                url.trim_start_matches("./")
+            )
+        } else {
+            url.to_string()
         }
-        StructKind::Tuple(fields) => {
-            // Use heading_level for Fields section (since level is already incremented in process_item)
-            output.push_str(&format!("{} Fields\n\n", "#".repeat(heading_level)));
-            output.push_str("| Index | Type | Documentation |\n");
-            output.push_str("|-------|------|---------------|\n");
+    };
+
+    // A reference definition, as in `[logo]: docs/logo.png "Logo"`
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() <= 3
+        && trimmed.starts_with('[')
+        && !trimmed.starts_with("[^")
+        && let Some(label_end) = trimmed.find("]:")
+    {
+        let (definition, destination) = trimmed.split_at(label_end + 2);
+        let url = destination.trim_start();
+        let (url, title) = url.split_at(url.find(' ').unwrap_or(url.len()));
+        return format!(
+            "{}{}{}{}{}",
+            &line[..line.len() - trimmed.len()],
+            definition,
+            &destination[..destination.len() - destination.trim_start().len()],
+            rebase(url),
+            title
+        );
+    }
 
-            for (i, field_opt) in fields.iter().enumerate() {
-                if let Some(field_id) = field_opt {
-                    if let Some(field_item) = data.index.get(field_id) {
-                        if let ItemEnum::StructField(field_type) = &field_item.inner {
-                            let docs = field_item
-                                .docs
-                                .as_deref()
-                                .unwrap_or("")
-                                .replace("\n", "<br>");
-                            output.push_str(&format!(
-                                "| {} | `{}` | {} |\n",
-                                i,
-                                format_type(field_type, data),
-                                docs
-                            ));
-                        }
-                    }
-                } else {
-                    output.push_str(&format!("| {} | `private` | *Private field* |\n", i));
-                }
-            }
-            output.push('\n');
+    let mut output = String::new();
+    let mut in_code = false;
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            in_code = !in_code;
+        } else if !in_code && rest.starts_with("](") {
+            let url = &rest[2..];
+            let url_len = url.find([')', ' ']).unwrap_or(url.len());
+            let (url, after) = url.split_at(url_len);
+            output.push_str("](");
+            output.push_str(&rebase(url));
+            rest = after;
+            continue;
         }
-        StructKind::Plain {
-            fields,
-            has_stripped_fields,
-        } => {
-            // Use heading_level for Fields section
-            output.push_str(&format!("{} Fields\n\n", "#".repeat(heading_level)));
-            output.push_str("| Name | Type | Documentation |\n");
+        output.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    output
+}
+
+/// Returns whether a link target is a relative URL to a file, like
+/// `docs/logo.png`, rather than an absolute URL, an anchor or an intra-doc link
+/// to a Rust path like `Vec` or `crate::Parser`.
+fn is_relative_asset_url(url: &str) -> bool {
+    let has_scheme = url
+        .split_once(':')
+        .is_some_and(|(scheme, _)| scheme.chars().all(|c| c.is_ascii_alphabetic()));
+    let path = url.split(['#', '?']).next().unwrap_or(url);
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    !url.is_empty()
+        && !url.starts_with(['#', '/'])
+        && !has_scheme
+        && !url.contains("::")
+        && (path.contains('/') || file_name.contains('.'))
+}
+
+/// Shifts an ATX heading in doc comments to nest under an item heading at
+/// `heading_level`, so `# Examples` under a `###` heading becomes `#### Examples`.
+/// Levels are capped at 6. Returns `None` if the line isn't a heading.
+fn nest_heading(line: &str, heading_level: usize) -> Option<String> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+    let rest = &trimmed[hashes..];
+    if !(1..=6).contains(&hashes) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    Some(format!(
+        "{}{}",
+        "#".repeat(std::cmp::min(hashes + heading_level, 6)),
+        rest
+    ))
+}
+
+/// Returns whether a code fence's info string marks a block rustdoc treats as Rust,
+/// which is any block without a language or with only doctest attributes.
+fn is_rust_code_block(info: &str) -> bool {
+    info.split([',', ' ', '\t'])
+        .filter(|token| !token.is_empty())
+        .all(|token| {
+            matches!(
+                token,
+                "rust"
+                    | "ignore"
+                    | "should_panic"
+                    | "no_run"
+                    | "compile_fail"
+                    | "test_harness"
+                    | "standalone_crate"
+            ) || token.starts_with("edition")
+                || token.starts_with("ignore-")
+        })
+}
+
+/// Parses a line as a Markdown code fence, returning the fence character, its
+/// length and the trimmed info string that follows it.
+fn parse_code_fence(line: &str) -> Option<(char, usize, &str)> {
+    let trimmed = line.trim_start();
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+
+    let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let fence_len = trimmed.chars().take_while(|c| *c == fence_char).count();
+    if fence_len < 3 {
+        return None;
+    }
+
+    Some((fence_char, fence_len, trimmed[fence_len..].trim()))
+}
+
+/// Returns a field's docs for its table cell, noting `PhantomData` marker fields
+/// that have no docs of their own.
+fn field_docs_cell(
+    field_item: &Item,
+    field_type: &Type,
+    data: &Crate,
+    options: &MarkdownOptions,
+) -> String {
+    match field_item.docs.as_deref() {
+        Some(docs) if !docs.trim().is_empty() => escape_table_cell(docs, options),
+        _ if is_phantom_data(field_type, data) => "*(marker, zero-sized)*".to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Returns whether a type is `core::marker::PhantomData`.
+fn is_phantom_data(ty: &Type, data: &Crate) -> bool {
+    let Type::ResolvedPath(path) = ty else {
+        return false;
+    };
+    match data.paths.get(&path.id) {
+        Some(summary) => summary
+            .path
+            .last()
+            .is_some_and(|name| name == "PhantomData"),
+        None => path.path.rsplit("::").next() == Some("PhantomData"),
+    }
+}
+
+/// Escapes text so it can be placed inside a single Markdown table cell.
+///
+/// Pipes would otherwise end the cell early and line breaks would end the row,
+/// so pipes are escaped, tabs become spaces and lines are kept to one line as
+/// chosen by `table_doc_mode`.
+fn escape_table_cell(text: &str, options: &MarkdownOptions) -> String {
+    let lines = text
+        .trim()
+        .lines()
+        .map(|line| line.trim_end().replace('\t', " ").replace('|', "\\|"));
+
+    match options.table_doc_mode {
+        TableDocMode::FirstLineOnly => lines
+            .map(|line| line.trim_start().to_string())
+            .find(|line| !line.is_empty())
+            .unwrap_or_default(),
+        TableDocMode::HtmlBreaks => lines.collect::<Vec<_>>().join("<br>"),
+        TableDocMode::Escaped => lines
+            .map(|line| line.trim_start().to_string())
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+fn process_module_details(
+    output: &mut String,
+    module_id: &Id,
+    module: &Module,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
+    if module.is_stripped {
+        output.push_str(&format!("{}\n\n", STRIPPED_MODULE_NOTE));
+    }
+
+    // Unless asked to keep the true depth, reset the level when entering a module
+    // to avoid excessive nesting, so module contents are always at a reasonable
+    // heading level
+    let level = if options.preserve_module_depth {
+        level
+    } else {
+        3
+    };
+    let items = items_within_depth(output, module_id, module, data, options);
+    process_items(output, &items, data, options, level);
+}
+
+/// Returns the module's items to render, leaving out its submodules if the module
+/// is already at `max_depth` and noting which ones were left out.
+fn items_within_depth(
+    output: &mut String,
+    module_id: &Id,
+    module: &Module,
+    data: &Crate,
+    options: &MarkdownOptions,
+) -> Vec<Id> {
+    let (items, submodules) = partition_by_depth(module_id, module, data, options);
+    let omitted: Vec<String> = submodules
+        .iter()
+        .filter(|id| !is_excluded(id, data, options))
+        .filter_map(|id| {
+            let path = match data.paths.get(id) {
+                Some(summary) => summary.path.join("::"),
+                None => data.index.get(id)?.name.clone()?,
+            };
+            Some(format!("`{}`", path))
+        })
+        .collect();
+    if !omitted.is_empty() {
+        output.push_str(&format!(
+            "*…further submodules omitted: {}*\n\n",
+            omitted.join(", ")
+        ));
+    }
+    items
+}
+
+/// Splits a module's items into those within `max_depth` and the submodules left
+/// out because the module is already at `max_depth`.
+fn partition_by_depth(
+    module_id: &Id,
+    module: &Module,
+    data: &Crate,
+    options: &MarkdownOptions,
+) -> (Vec<Id>, Vec<Id>) {
+    let depth = data
+        .paths
+        .get(module_id)
+        .map_or(0, |summary| summary.path.len().saturating_sub(1));
+    if options.max_depth.is_none_or(|max_depth| depth < max_depth) {
+        return (module.items.clone(), Vec::new());
+    }
+
+    let (submodules, items) = module.items.iter().partition(|id| {
+        matches!(
+            data.index.get(id).map(|item| &item.inner),
+            Some(ItemEnum::Module(_))
+        )
+    });
+    (items, submodules)
+}
+
+/// Common traits summarized in a type's capability line, in display order.
+const CAPABILITY_TRAITS: &[&str] = &[
+    "Clone",
+    "Copy",
+    "Debug",
+    "Default",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+    "Serialize",
+    "Deserialize",
+];
+
+/// Adds a one-line summary of which common traits (`Clone`, `Debug`, ...) a type
+/// implements, as an overview before its full list of implementations.
+fn push_capabilities(output: &mut String, impls: &[Id], data: &Crate) {
+    let implemented: Vec<&str> = impls
+        .iter()
+        .filter_map(|impl_id| match &data.index.get(impl_id)?.inner {
+            ItemEnum::Impl(impl_) if !impl_.is_negative => impl_.trait_.as_ref(),
+            _ => None,
+        })
+        .map(|trait_| trait_.path.rsplit("::").next().unwrap_or(&trait_.path))
+        .collect();
+
+    let capabilities: Vec<&str> = CAPABILITY_TRAITS
+        .iter()
+        .copied()
+        .filter(|name| implemented.contains(name))
+        .collect();
+
+    if !capabilities.is_empty() {
+        output.push_str(&format!("**Derives:** {}\n\n", capabilities.join(", ")));
+    }
+}
+
+fn process_struct_details(
+    output: &mut String,
+    struct_: &Struct,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
+    // Cap heading level at 6 (maximum valid Markdown heading level)
+    let heading_level = std::cmp::min(level, 6);
+    push_capabilities(output, &struct_.impls, data);
+    // Detail fields based on struct kind
+    match &struct_.kind {
+        StructKind::Unit => {
+            // Nothing to detail for unit structs
+        }
+        StructKind::Tuple(fields) => {
+            // Use heading_level for Fields section (since level is already incremented in process_item)
+            output.push_str(&format!("{} Fields\n\n", "#".repeat(heading_level)));
+            output.push_str("| Index | Type | Documentation |\n");
+            output.push_str("|-------|------|---------------|\n");
+
+            for (i, field_opt) in fields.iter().enumerate() {
+                if let Some(field_id) = field_opt {
+                    if let Some(field_item) = data.index.get(field_id) {
+                        if let ItemEnum::StructField(field_type) = &field_item.inner {
+                            let docs = field_docs_cell(field_item, field_type, data, options);
+                            output.push_str(&format!(
+                                "| {} | `{}` | {} |\n",
+                                i,
+                                format_type(field_type, data),
+                                docs
+                            ));
+                        }
+                    }
+                } else {
+                    output.push_str(&format!(
+                        "| {} | {} | {} |\n",
+                        i, PRIVATE_FIELD_TYPE_CELL, PRIVATE_FIELD_CELL
+                    ));
+                }
+            }
+            output.push('\n');
+        }
+        StructKind::Plain {
+            fields,
+            has_stripped_fields,
+        } => {
+            // Use heading_level for Fields section
+            output.push_str(&format!("{} Fields\n\n", "#".repeat(heading_level)));
+            output.push_str("| Name | Type | Documentation |\n");
             output.push_str("|------|------|---------------|\n");
 
             for &field_id in fields {
                 if let Some(field_item) = data.index.get(&field_id) {
                     if let Some(field_name) = &field_item.name {
                         if let ItemEnum::StructField(field_type) = &field_item.inner {
-                            let docs = field_item
-                                .docs
-                                .as_deref()
-                                .unwrap_or("")
-                                .replace("\n", "<br>");
+                            let docs = field_docs_cell(field_item, field_type, data, options);
                             output.push_str(&format!(
                                 "| `{}` | `{}` | {} |\n",
                                 field_name,
@@ -1515,134 +2828,29 @@ fn process_struct_details(output: &mut String, struct_: &Struct, data: &Crate, l
             }
 
             if *has_stripped_fields {
-                output.push_str("| *private fields* | ... | *Some fields have been omitted* |\n");
+                output.push_str(&format!(
+                    "| {} | ... | {} |\n",
+                    PRIVATE_FIELDS_CELL, FIELDS_OMITTED_CELL
+                ));
             }
 
             output.push('\n');
         }
     }
 
-    // Process impls
-    if !struct_.impls.is_empty() {
-        // Use heading_level for Implementations section
-        output.push_str(&format!(
-            "{} Implementations\n\n",
-            "#".repeat(heading_level)
-        ));
-
-        // Group impls by trait
-        let mut trait_impls: std::collections::HashMap<String, Vec<Id>> =
-            std::collections::HashMap::new();
-        let mut inherent_impls: Vec<Id> = Vec::new();
-
-        for &impl_id in &struct_.impls {
-            if let Some(impl_item) = data.index.get(&impl_id) {
-                if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                    if let Some(trait_) = &impl_.trait_ {
-                        let trait_name = trait_.path.clone();
-                        trait_impls.entry(trait_name).or_default().push(impl_id);
-                    } else {
-                        // Inherent impl
-                        inherent_impls.push(impl_id);
-                    }
-                }
-            }
-        }
-
-        // First list inherent impls
-        if !inherent_impls.is_empty() {
-            // Use level+1 for Methods (one level deeper than Implementations)
-            output.push_str(&format!(
-                "{} Methods\n\n",
-                "#".repeat(std::cmp::min(heading_level + 1, 6))
-            ));
-            for &impl_id in &inherent_impls {
-                if let Some(impl_item) = data.index.get(&impl_id) {
-                    if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                        for &item_id in &impl_.items {
-                            if let Some(method_item) = data.index.get(&item_id) {
-                                if let ItemEnum::Function(_) = &method_item.inner {
-                                    // Format method signature
-                                    let mut method_signature = String::new();
-                                    format_item_signature(&mut method_signature, method_item, data);
-
-                                    // Output with proper code block formatting
-                                    output.push_str("- ```rust\n  ");
-                                    output.push_str(&method_signature.trim());
-                                    output.push_str("\n  ```");
-
-                                    // Add documentation if available
-                                    if let Some(docs) = &method_item.docs {
-                                        if let Some(first_line) = docs.lines().next() {
-                                            if !first_line.trim().is_empty() {
-                                                output.push_str(&format!("\n  {}", first_line));
-                                            }
-                                        }
-                                    }
-                                    output.push_str("\n\n");
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Then list trait impls
-        if !trait_impls.is_empty() {
-            // Use level+1 for Trait Implementations (one level deeper than Implementations)
-            output.push_str(&format!(
-                "{} Trait Implementations\n\n",
-                "#".repeat(std::cmp::min(heading_level + 1, 6))
-            ));
-            // Sort trait implementations alphabetically for deterministic output
-            let mut sorted_trait_impls: Vec<_> = trait_impls.into_iter().collect();
-            sorted_trait_impls.sort_by(|a, b| a.0.cmp(&b.0));
-            for (trait_name, impls) in sorted_trait_impls {
-                output.push_str(&format!("- **{}**\n", trait_name));
-                for &impl_id in &impls {
-                    if let Some(impl_item) = data.index.get(&impl_id) {
-                        if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                            for &item_id in &impl_.items {
-                                if let Some(method_item) = data.index.get(&item_id) {
-                                    if let ItemEnum::Function(_) = &method_item.inner {
-                                        // Format method signature
-                                        let mut method_signature = String::new();
-                                        format_item_signature(
-                                            &mut method_signature,
-                                            method_item,
-                                            data,
-                                        );
-
-                                        // Output with proper code block formatting
-                                        output.push_str("  - ```rust\n    ");
-                                        output.push_str(&method_signature.trim());
-                                        output.push_str("\n    ```");
-
-                                        // Add documentation if available
-                                        if let Some(docs) = &method_item.docs {
-                                            if let Some(first_line) = docs.lines().next() {
-                                                if !first_line.trim().is_empty() {
-                                                    output
-                                                        .push_str(&format!("\n    {}", first_line));
-                                                }
-                                            }
-                                        }
-                                        output.push_str("\n\n");
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    process_type_impls(output, &struct_.impls, data, options, heading_level);
 }
 
-fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level: usize) {
+fn process_enum_details(
+    output: &mut String,
+    enum_: &Enum,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
     // Cap heading level at 6 (maximum valid Markdown heading level)
     let heading_level = std::cmp::min(level, 6);
+    push_capabilities(output, &enum_.impls, data);
     // Detail variants with proper nesting
     output.push_str(&format!("{} Variants\n\n", "#".repeat(heading_level)));
 
@@ -1657,21 +2865,22 @@ fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level:
                     variant_name
                 ));
 
+                if let Some(note) = non_exhaustive_note(variant_item) {
+                    output.push_str(&format!("> {}\n\n", note));
+                }
+
                 // Add variant docs if available
                 if let Some(docs) = &variant_item.docs {
-                    output.push_str(&format!("{}\n\n", docs));
+                    output.push_str(&format!(
+                        "{}\n\n",
+                        render_docs(docs, variant_heading_level, options)
+                    ));
                 }
 
                 if let ItemEnum::Variant(variant) = &variant_item.inner {
                     match &variant.kind {
                         VariantKind::Plain => {
                             // Nothing additional to display for plain variants
-                            if let Some(discriminant) = &variant.discriminant {
-                                output.push_str(&format!(
-                                    "Discriminant: `{}`\n\n",
-                                    discriminant.expr
-                                ));
-                            }
                         }
                         VariantKind::Tuple(fields) => {
                             output.push_str("Fields:\n\n");
@@ -1683,11 +2892,9 @@ fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level:
                                     if let Some(field_item) = data.index.get(field_id) {
                                         if let ItemEnum::StructField(field_type) = &field_item.inner
                                         {
-                                            let docs = field_item
-                                                .docs
-                                                .as_deref()
-                                                .unwrap_or("")
-                                                .replace("\n", "<br>");
+                                            let docs = field_docs_cell(
+                                                field_item, field_type, data, options,
+                                            );
                                             output.push_str(&format!(
                                                 "| {} | `{}` | {} |\n",
                                                 i,
@@ -1698,8 +2905,8 @@ fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level:
                                     }
                                 } else {
                                     output.push_str(&format!(
-                                        "| {} | `private` | *Private field* |\n",
-                                        i
+                                        "| {} | {} | {} |\n",
+                                        i, PRIVATE_FIELD_TYPE_CELL, PRIVATE_FIELD_CELL
                                     ));
                                 }
                             }
@@ -1718,11 +2925,9 @@ fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level:
                                     if let Some(field_name) = &field_item.name {
                                         if let ItemEnum::StructField(field_type) = &field_item.inner
                                         {
-                                            let docs = field_item
-                                                .docs
-                                                .as_deref()
-                                                .unwrap_or("")
-                                                .replace("\n", "<br>");
+                                            let docs = field_docs_cell(
+                                                field_item, field_type, data, options,
+                                            );
                                             output.push_str(&format!(
                                                 "| `{}` | `{}` | {} |\n",
                                                 field_name,
@@ -1735,16 +2940,30 @@ fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level:
                             }
 
                             if *has_stripped_fields {
-                                output.push_str("| *private fields* | ... | *Some fields have been omitted* |\n");
+                                output.push_str(&format!(
+                                    "| {} | ... | {} |\n",
+                                    PRIVATE_FIELDS_CELL, FIELDS_OMITTED_CELL
+                                ));
                             }
 
                             output.push('\n');
                         }
                     }
 
+                    // Show the source expression too when it differs from the value,
+                    // e.g. `1 << 3` evaluating to `8`
                     if let Some(discriminant) = &variant.discriminant {
-                        output
-                            .push_str(&format!("Discriminant value: `{}`\n\n", discriminant.value));
+                        if discriminant.expr == discriminant.value {
+                            output.push_str(&format!(
+                                "Discriminant value: `{}`\n\n",
+                                discriminant.value
+                            ));
+                        } else {
+                            output.push_str(&format!(
+                                "Discriminant expression: `{}` (value `{}`)\n\n",
+                                discriminant.expr, discriminant.value
+                            ));
+                        }
                     }
                 }
             }
@@ -1752,128 +2971,22 @@ fn process_enum_details(output: &mut String, enum_: &Enum, data: &Crate, level:
     }
 
     if enum_.has_stripped_variants {
-        output.push_str(
-            "*Note: Some variants have been omitted because they are private or hidden.*\n\n",
-        );
+        output.push_str(&format!("{}\n\n", VARIANTS_OMITTED_NOTE));
     }
 
-    // Process impls (same as for struct)
-    if !enum_.impls.is_empty() {
-        output.push_str(&format!(
-            "{} Implementations\n\n",
-            "#".repeat(heading_level)
-        ));
-
-        // Group impls by trait
-        let mut trait_impls: std::collections::HashMap<String, Vec<Id>> =
-            std::collections::HashMap::new();
-        let mut inherent_impls: Vec<Id> = Vec::new();
-
-        for &impl_id in &enum_.impls {
-            if let Some(impl_item) = data.index.get(&impl_id) {
-                if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                    if let Some(trait_) = &impl_.trait_ {
-                        let trait_name = trait_.path.clone();
-                        trait_impls.entry(trait_name).or_default().push(impl_id);
-                    } else {
-                        // Inherent impl
-                        inherent_impls.push(impl_id);
-                    }
-                }
-            }
-        }
-
-        // First list inherent impls
-        if !inherent_impls.is_empty() {
-            let methods_level = std::cmp::min(heading_level + 1, 6);
-            output.push_str(&format!("{} Methods\n\n", "#".repeat(methods_level)));
-            for &impl_id in &inherent_impls {
-                if let Some(impl_item) = data.index.get(&impl_id) {
-                    if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                        for &item_id in &impl_.items {
-                            if let Some(method_item) = data.index.get(&item_id) {
-                                if let ItemEnum::Function(_) = &method_item.inner {
-                                    // Format method signature
-                                    let mut method_signature = String::new();
-                                    format_item_signature(&mut method_signature, method_item, data);
-
-                                    // Output with proper code block formatting
-                                    output.push_str("- ```rust\n  ");
-                                    output.push_str(&method_signature.trim());
-                                    output.push_str("\n  ```");
-
-                                    // Add documentation if available
-                                    if let Some(docs) = &method_item.docs {
-                                        if let Some(first_line) = docs.lines().next() {
-                                            if !first_line.trim().is_empty() {
-                                                output.push_str(&format!("\n  {}", first_line));
-                                            }
-                                        }
-                                    }
-                                    output.push_str("\n\n");
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Then list trait impls
-        if !trait_impls.is_empty() {
-            let trait_impl_level = std::cmp::min(heading_level + 1, 6);
-            output.push_str(&format!(
-                "{} Trait Implementations\n\n",
-                "#".repeat(trait_impl_level)
-            ));
-            // Sort trait implementations alphabetically for deterministic output
-            let mut sorted_trait_impls: Vec<_> = trait_impls.into_iter().collect();
-            sorted_trait_impls.sort_by(|a, b| a.0.cmp(&b.0));
-            for (trait_name, impls) in sorted_trait_impls {
-                output.push_str(&format!("- **{}**\n", trait_name));
-                for &impl_id in &impls {
-                    if let Some(impl_item) = data.index.get(&impl_id) {
-                        if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                            for &item_id in &impl_.items {
-                                if let Some(method_item) = data.index.get(&item_id) {
-                                    if let ItemEnum::Function(_) = &method_item.inner {
-                                        // Format method signature
-                                        let mut method_signature = String::new();
-                                        format_item_signature(
-                                            &mut method_signature,
-                                            method_item,
-                                            data,
-                                        );
-
-                                        // Output with proper code block formatting
-                                        output.push_str("  - ```rust\n    ");
-                                        output.push_str(&method_signature.trim());
-                                        output.push_str("\n    ```");
-
-                                        // Add documentation if available
-                                        if let Some(docs) = &method_item.docs {
-                                            if let Some(first_line) = docs.lines().next() {
-                                                if !first_line.trim().is_empty() {
-                                                    output
-                                                        .push_str(&format!("\n    {}", first_line));
-                                                }
-                                            }
-                                        }
-                                        output.push_str("\n\n");
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    process_type_impls(output, &enum_.impls, data, options, heading_level);
 }
 
-fn process_union_details(output: &mut String, union_: &Union, data: &Crate, level: usize) {
+fn process_union_details(
+    output: &mut String,
+    union_: &Union,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
     // Cap heading level at 6 (maximum valid Markdown heading level)
     let heading_level = std::cmp::min(level, 6);
+    push_capabilities(output, &union_.impls, data);
     // Detail fields
     output.push_str(&format!("{} Fields\n\n", "#".repeat(heading_level)));
     output.push_str("| Name | Type | Documentation |\n");
@@ -1883,11 +2996,7 @@ fn process_union_details(output: &mut String, union_: &Union, data: &Crate, leve
         if let Some(field_item) = data.index.get(&field_id) {
             if let Some(field_name) = &field_item.name {
                 if let ItemEnum::StructField(field_type) = &field_item.inner {
-                    let docs = field_item
-                        .docs
-                        .as_deref()
-                        .unwrap_or("")
-                        .replace("\n", "<br>");
+                    let docs = field_docs_cell(field_item, field_type, data, options);
                     output.push_str(&format!(
                         "| `{}` | `{}` | {} |\n",
                         field_name,
@@ -1900,101 +3009,287 @@ fn process_union_details(output: &mut String, union_: &Union, data: &Crate, leve
     }
 
     if union_.has_stripped_fields {
-        output.push_str("| *private fields* | ... | *Some fields have been omitted* |\n");
+        output.push_str(&format!(
+            "| {} | ... | {} |\n",
+            PRIVATE_FIELDS_CELL, FIELDS_OMITTED_CELL
+        ));
     }
 
     output.push('\n');
 
-    // Process impls
-    if !union_.impls.is_empty() {
-        output.push_str(&format!(
-            "{} Implementations\n\n",
-            "#".repeat(heading_level)
-        ));
+    process_type_impls(output, &union_.impls, data, options, heading_level);
+}
+
+/// Lists the methods of a struct, enum or union's impls, with inherent methods
+/// first and then trait impls grouped by trait.
+fn process_type_impls(
+    output: &mut String,
+    impls: &[Id],
+    data: &Crate,
+    options: &MarkdownOptions,
+    heading_level: usize,
+) {
+    if impls.is_empty() {
+        return;
+    }
 
-        // Group impls by trait
-        let mut trait_impls: std::collections::HashMap<String, Vec<Id>> =
-            std::collections::HashMap::new();
-        let mut inherent_impls: Vec<Id> = Vec::new();
+    output.push_str(&format!(
+        "{} Implementations\n\n",
+        "#".repeat(heading_level)
+    ));
+
+    // Group impls by trait
+    let mut trait_impls: std::collections::HashMap<String, Vec<Id>> =
+        std::collections::HashMap::new();
+    let mut inherent_impls: Vec<Id> = Vec::new();
+
+    for impl_id in dedup_impls(impls, data) {
+        if let Some(impl_item) = data.index.get(&impl_id)
+            && let ItemEnum::Impl(impl_) = &impl_item.inner
+        {
+            if let Some(trait_) = &impl_.trait_ {
+                // Keep `unsafe` and negative (`!Send`) impls apart from the rest,
+                // and impls of the same trait with different args (`From<String>`,
+                // `From<&str>`) apart from each other
+                let mut trait_args = String::new();
+                if let Some(args) = &trait_.args {
+                    format_generic_args(&mut trait_args, args, data);
+                }
+                // Show the associated types the impl sets, e.g. `Iterator<Item = u8>`
+                let bindings: Vec<String> = impl_
+                    .items
+                    .iter()
+                    .filter_map(|id| data.index.get(id))
+                    .filter_map(|item| match &item.inner {
+                        ItemEnum::AssocType {
+                            type_: Some(type_), ..
+                        } => Some(format!(
+                            "{} = {}",
+                            item.name.as_deref()?,
+                            format_type(type_, data)
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+                if !bindings.is_empty() {
+                    match trait_args.strip_suffix('>') {
+                        Some(args) => trait_args = format!("{}, {}>", args, bindings.join(", ")),
+                        None => trait_args = format!("<{}>", bindings.join(", ")),
+                    }
+                }
+                let trait_name = format!(
+                    "{}{}{}{}",
+                    if impl_.is_unsafe { "unsafe " } else { "" },
+                    if impl_.is_negative { "!" } else { "" },
+                    trait_.path,
+                    trait_args
+                );
+                trait_impls.entry(trait_name).or_default().push(impl_id);
+            } else {
+                // Inherent impl
+                inherent_impls.push(impl_id);
+            }
+        }
+    }
 
-        for &impl_id in &union_.impls {
+    // First list inherent impls
+    if !inherent_impls.is_empty() {
+        // Use level+1 for Methods (one level deeper than Implementations)
+        output.push_str(&format!(
+            "{} Methods\n\n",
+            "#".repeat(std::cmp::min(heading_level + 1, 6))
+        ));
+        for &impl_id in &inherent_impls {
             if let Some(impl_item) = data.index.get(&impl_id) {
                 if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                    if let Some(trait_) = &impl_.trait_ {
-                        let trait_name = trait_.path.clone();
-                        trait_impls.entry(trait_name).or_default().push(impl_id);
-                    } else {
-                        // Inherent impl
-                        inherent_impls.push(impl_id);
+                    // Keep the impl block's own docs ahead of its methods
+                    if let Some(docs) = impl_item.docs.as_deref().filter(|d| !d.trim().is_empty()) {
+                        output.push_str(&format!(
+                            "{}\n\n",
+                            render_docs(docs, heading_level + 1, options)
+                        ));
+                    }
+                    for item_id in impl_items_in_order(impl_, data, options) {
+                        if let Some(method_item) = data
+                            .index
+                            .get(&item_id)
+                            .filter(|item| !is_hidden(item, options))
+                            && let ItemEnum::Function(_) = &method_item.inner
+                        {
+                            // Format method signature
+                            let mut method_signature = String::new();
+                            format_item_signature(
+                                &mut method_signature,
+                                method_item,
+                                data,
+                                options,
+                            );
+
+                            // Output with proper code block formatting
+                            push_code_list_item(
+                                output,
+                                method_signature.trim(),
+                                doc_summary(method_item),
+                                0,
+                                options,
+                            );
+                        }
                     }
                 }
             }
         }
+    }
 
-        // First list inherent impls
-        if !inherent_impls.is_empty() {
-            let methods_level = std::cmp::min(heading_level + 1, 6);
-            output.push_str(&format!("{} Methods\n\n", "#".repeat(methods_level)));
-            for &impl_id in &inherent_impls {
+    // Then list trait impls
+    if !trait_impls.is_empty() {
+        // Use level+1 for Trait Implementations (one level deeper than Implementations)
+        output.push_str(&format!(
+            "{} Trait Implementations\n\n",
+            "#".repeat(std::cmp::min(heading_level + 1, 6))
+        ));
+        // Sort trait implementations alphabetically for deterministic output
+        let mut sorted_trait_impls: Vec<_> = trait_impls.into_iter().collect();
+        sorted_trait_impls.sort_by_key(|(trait_name, _)| {
+            let path = trait_name
+                .trim_start_matches("unsafe ")
+                .trim_start_matches('!');
+            (path.to_string(), trait_name.clone())
+        });
+        for (trait_name, impls) in sorted_trait_impls {
+            // In a code span, so generic args like `<String>` aren't taken for HTML
+            output.push_str(&format!("- **`{}`**\n", trait_name));
+            for &impl_id in &impls {
                 if let Some(impl_item) = data.index.get(&impl_id) {
                     if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                        for &item_id in &impl_.items {
-                            if let Some(method_item) = data.index.get(&item_id) {
-                                if let ItemEnum::Function(_) = &method_item.inner {
-                                    if let Some(name) = &method_item.name {
-                                        output.push_str(&format!("- `{}`: ", name));
-                                        if let Some(docs) = &method_item.docs {
-                                            let first_line = docs.lines().next().unwrap_or("");
-                                            output.push_str(first_line);
-                                        }
-                                        output.push('\n');
-                                    }
-                                }
+                        if let Some(summary) = doc_summary(impl_item) {
+                            output.push_str(&format!(
+                                "{}{}\n\n",
+                                " ".repeat(options.list_indent),
+                                summary
+                            ));
+                        }
+                        for item_id in impl_items_in_order(impl_, data, options) {
+                            if let Some(method_item) = data
+                                .index
+                                .get(&item_id)
+                                .filter(|item| !is_hidden(item, options))
+                                && let ItemEnum::Function(_) = &method_item.inner
+                            {
+                                // Format method signature
+                                let mut method_signature = String::new();
+                                format_item_signature(
+                                    &mut method_signature,
+                                    method_item,
+                                    data,
+                                    options,
+                                );
+
+                                // Output with proper code block formatting
+                                push_code_list_item(
+                                    output,
+                                    method_signature.trim(),
+                                    doc_summary(method_item),
+                                    1,
+                                    options,
+                                );
                             }
                         }
                     }
                 }
             }
-            output.push('\n');
         }
+    }
 
-        // Then list trait impls
-        if !trait_impls.is_empty() {
-            let trait_impl_level = std::cmp::min(heading_level + 1, 6);
-            output.push_str(&format!(
-                "{} Trait Implementations\n\n",
-                "#".repeat(trait_impl_level)
-            ));
-            // Sort trait implementations alphabetically for deterministic output
-            let mut sorted_trait_impls: Vec<_> = trait_impls.into_iter().collect();
-            sorted_trait_impls.sort_by(|a, b| a.0.cmp(&b.0));
-            for (trait_name, impls) in sorted_trait_impls {
-                output.push_str(&format!("- **{}**\n", trait_name));
-                for &impl_id in &impls {
-                    if let Some(impl_item) = data.index.get(&impl_id) {
-                        if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                            for &item_id in &impl_.items {
-                                if let Some(method_item) = data.index.get(&item_id) {
-                                    if let Some(name) = &method_item.name {
-                                        output.push_str(&format!("  - `{}`: ", name));
-                                        if let Some(docs) = &method_item.docs {
-                                            let first_line = docs.lines().next().unwrap_or("");
-                                            output.push_str(first_line);
-                                        }
-                                        output.push('\n');
-                                    }
-                                }
-                            }
-                        }
+    process_deref_methods(output, impls, data, options, heading_level);
+}
+
+/// Lists the inherent methods reachable through a `Deref` impl among `impls`,
+/// like rustdoc's "Methods from `Deref<Target = U>`" section.
+fn process_deref_methods(
+    output: &mut String,
+    impls: &[Id],
+    data: &Crate,
+    options: &MarkdownOptions,
+    heading_level: usize,
+) {
+    // Resolve the target through the impl's `Target` associated type
+    let Some(target) = impls
+        .iter()
+        .find_map(|impl_id| match &data.index.get(impl_id)?.inner {
+            ItemEnum::Impl(impl_)
+                if impl_.trait_.as_ref().is_some_and(|trait_| {
+                    trait_.path == "Deref" || trait_.path.ends_with("::Deref")
+                }) =>
+            {
+                impl_.items.iter().find_map(|item_id| {
+                    let assoc_item = data.index.get(item_id)?;
+                    match &assoc_item.inner {
+                        ItemEnum::AssocType {
+                            type_: Some(type_), ..
+                        } if assoc_item.name.as_deref() == Some("Target") => Some(type_),
+                        _ => None,
                     }
-                }
+                })
+            }
+            _ => None,
+        })
+    else {
+        return;
+    };
+
+    // Only local types have their impls in the index
+    let target_impls = match target {
+        Type::ResolvedPath(path) => match data.index.get(&path.id).map(|item| &item.inner) {
+            Some(ItemEnum::Struct(struct_)) => &struct_.impls,
+            Some(ItemEnum::Enum(enum_)) => &enum_.impls,
+            Some(ItemEnum::Union(union_)) => &union_.impls,
+            _ => return,
+        },
+        _ => return,
+    };
+
+    let methods: Vec<&Item> = target_impls
+        .iter()
+        .filter_map(|impl_id| match &data.index.get(impl_id)?.inner {
+            ItemEnum::Impl(impl_) if impl_.trait_.is_none() => Some(&impl_.items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item_id| data.index.get(item_id))
+        .filter(|item| matches!(item.inner, ItemEnum::Function(_)) && !is_hidden(item, options))
+        .collect();
+    if methods.is_empty() {
+        return;
+    }
+
+    // The trait impl list above may not end in a blank line
+    if !output.ends_with("\n\n") {
+        output.push('\n');
+    }
+    output.push_str(&format!(
+        "{} Methods from `Deref<Target = {}>`\n\n",
+        "#".repeat(std::cmp::min(heading_level + 1, 6)),
+        format_type(target, data)
+    ));
+    for method_item in methods {
+        if let Some(name) = &method_item.name {
+            output.push_str(&format!("- `{}`", name));
+            if let Some(summary) = doc_summary(method_item) {
+                output.push_str(&format!(": {}", summary));
             }
             output.push('\n');
         }
     }
+    output.push('\n');
 }
 
-fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, level: usize) {
+fn process_trait_details(
+    output: &mut String,
+    trait_: &Trait,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
     // Cap heading level at 6 (maximum valid Markdown heading level)
     let heading_level = std::cmp::min(level, 6);
     // Special traits info
@@ -2010,6 +3305,20 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
         );
     }
 
+    // Surface the supertraits, which are easy to miss in the signature
+    if !trait_.bounds.is_empty() {
+        let supertraits: Vec<String> = trait_
+            .bounds
+            .iter()
+            .map(|bound| {
+                let mut rendered = String::new();
+                format_bounds(&mut rendered, std::slice::from_ref(bound), data);
+                format!("`{}`", rendered)
+            })
+            .collect();
+        output.push_str(&format!("**Extends:** {}\n\n", supertraits.join(", ")));
+    }
+
     // Associated items
     if !trait_.items.is_empty() {
         // Group items by kind
@@ -2019,7 +3328,11 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
         let mut assoc_consts = Vec::new();
 
         for &item_id in &trait_.items {
-            if let Some(item) = data.index.get(&item_id) {
+            if let Some(item) = data
+                .index
+                .get(&item_id)
+                .filter(|item| !is_hidden(item, options))
+            {
                 match &item.inner {
                     ItemEnum::Function(function) => {
                         if function.has_body {
@@ -2055,12 +3368,8 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
                     if let Some(type_item) = data.index.get(&type_id) {
                         if let Some(name) = &type_item.name {
                             output.push_str(&format!("- `{}`", name));
-                            if let Some(docs) = &type_item.docs {
-                                if let Some(first_line) = docs.lines().next() {
-                                    if !first_line.trim().is_empty() {
-                                        output.push_str(&format!(": {}", first_line));
-                                    }
-                                }
+                            if let Some(summary) = doc_summary(type_item) {
+                                output.push_str(&format!(": {}", summary));
                             }
                             output.push('\n');
                         }
@@ -2076,14 +3385,14 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
                 ));
                 for &const_id in &assoc_consts {
                     if let Some(const_item) = data.index.get(&const_id) {
-                        if let Some(name) = &const_item.name {
-                            output.push_str(&format!("- `{}`", name));
-                            if let Some(docs) = &const_item.docs {
-                                if let Some(first_line) = docs.lines().next() {
-                                    if !first_line.trim().is_empty() {
-                                        output.push_str(&format!(": {}", first_line));
-                                    }
-                                }
+                        if const_item.name.is_some() {
+                            // Show the full declaration rather than just the name, as
+                            // the type may depend on the trait's generic parameters
+                            let mut const_signature = String::new();
+                            format_item_signature(&mut const_signature, const_item, data, options);
+                            output.push_str(&format!("- `{}`", const_signature));
+                            if let Some(summary) = doc_summary(const_item) {
+                                output.push_str(&format!(": {}", summary));
                             }
                             output.push('\n');
                         }
@@ -2099,20 +3408,20 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
                 ));
                 for &method_id in &required_methods {
                     if let Some(method_item) = data.index.get(&method_id) {
-                        if let Some(name) = &method_item.name {
-                            output.push_str(&format!("- `{}`", name));
-                            if let Some(docs) = &method_item.docs {
-                                if let Some(first_line) = docs.lines().next() {
-                                    if !first_line.trim().is_empty() {
-                                        output.push_str(&format!(": {}", first_line));
-                                    }
-                                }
-                            }
-                            output.push('\n');
-                        }
+                        // Show the full signature, as the parameters and return type
+                        // are what implementors have to match
+                        let mut method_signature = String::new();
+                        format_item_signature(&mut method_signature, method_item, data, options);
+
+                        push_code_list_item(
+                            output,
+                            method_signature.trim(),
+                            doc_summary(method_item),
+                            0,
+                            options,
+                        );
                     }
                 }
-                output.push('\n');
             }
         }
 
@@ -2127,22 +3436,16 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
                     if let ItemEnum::Function(_) = &method_item.inner {
                         // Format method signature
                         let mut method_signature = String::new();
-                        format_item_signature(&mut method_signature, method_item, data);
+                        format_item_signature(&mut method_signature, method_item, data, options);
 
                         // Output with proper code block formatting
-                        output.push_str("- ```rust\n  ");
-                        output.push_str(&method_signature.trim());
-                        output.push_str("\n  ```");
-
-                        // Add documentation if available
-                        if let Some(docs) = &method_item.docs {
-                            if let Some(first_line) = docs.lines().next() {
-                                if !first_line.trim().is_empty() {
-                                    output.push_str(&format!("\n  {}", first_line));
-                                }
-                            }
-                        }
-                        output.push_str("\n\n");
+                        push_code_list_item(
+                            output,
+                            method_signature.trim(),
+                            doc_summary(method_item),
+                            0,
+                            options,
+                        );
                     }
                 }
             }
@@ -2157,28 +3460,107 @@ fn process_trait_details(output: &mut String, trait_: &Trait, data: &Crate, leve
         ));
         output.push_str("This trait is implemented for the following types:\n\n");
 
-        for &impl_id in &trait_.implementations {
-            if let Some(impl_item) = data.index.get(&impl_id) {
-                if let ItemEnum::Impl(impl_) = &impl_item.inner {
-                    output.push_str(&format!("- `{}`", format_type(&impl_.for_, data)));
-                    // Add generics if present
-                    if !impl_.generics.params.is_empty() {
-                        let mut generics_str = String::new();
-                        format_generics(&mut generics_str, &impl_.generics, data);
-                        if generics_str != "<>" {
-                            output.push_str(" with ");
-                            output.push_str(&generics_str);
+        // Sort implementors by their formatted type for deterministic output,
+        // falling back to the impl id when two impls format identically
+        let mut implementors: Vec<(String, Id, &Item, &Impl)> =
+            dedup_impls(&trait_.implementations, data)
+                .into_iter()
+                .filter_map(|impl_id| {
+                    let impl_item = data.index.get(&impl_id)?;
+                    match &impl_item.inner {
+                        ItemEnum::Impl(impl_) => {
+                            Some((format_type(&impl_.for_, data), impl_id, impl_item, impl_))
                         }
+                        _ => None,
                     }
-                    output.push('\n');
+                })
+                .collect();
+        implementors.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.0.cmp(&b.1.0)));
+
+        let total = implementors.len();
+        let shown = options.max_implementors.unwrap_or(total).min(total);
+        for (_, _, impl_item, impl_) in implementors.into_iter().take(shown) {
+            // Show the full impl header so generic bounds and where clauses, which
+            // decide when the impl applies, aren't lost
+            let mut impl_header = String::new();
+            format_impl_header(&mut impl_header, impl_, data);
+            output.push_str(&format!(
+                "- `{}`",
+                impl_header.split_whitespace().collect::<Vec<_>>().join(" ")
+            ));
+            if let Some(summary) = doc_summary(impl_item) {
+                output.push_str(&format!(": {}", summary));
+            }
+            output.push('\n');
+        }
+        if total > shown {
+            output.push_str(&format!("- …and {} more\n", total - shown));
+        }
+        output.push('\n');
+    }
+}
+
+/// Returns an impl's items in source order, or sorted by name if `sort_methods`
+/// is set.
+fn impl_items_in_order(impl_: &Impl, data: &Crate, options: &MarkdownOptions) -> Vec<Id> {
+    let mut items = impl_.items.clone();
+    if options.sort_methods {
+        items.sort_by_key(|id| data.index.get(id).and_then(|item| item.name.clone()));
+    }
+    items
+}
+
+/// Drops impls whose header renders the same as an earlier one, which some
+/// rustdoc versions emit for both a synthetic and a real impl of the same trait.
+/// The non-synthetic impl is kept when there's a choice.
+fn dedup_impls(impls: &[Id], data: &Crate) -> Vec<Id> {
+    let mut kept: Vec<(String, Id, bool)> = Vec::new();
+    for &impl_id in impls {
+        let Some(ItemEnum::Impl(impl_)) = data.index.get(&impl_id).map(|item| &item.inner) else {
+            kept.push((String::new(), impl_id, false));
+            continue;
+        };
+        let mut header = String::new();
+        format_impl_header(&mut header, impl_, data);
+        let header = header.split_whitespace().collect::<Vec<_>>().join(" ");
+        match kept
+            .iter_mut()
+            .find(|(existing, _, _)| !existing.is_empty() && *existing == header)
+        {
+            Some(entry) => {
+                if entry.2 && !impl_.is_synthetic {
+                    *entry = (header, impl_id, false);
                 }
             }
+            None => kept.push((header, impl_id, impl_.is_synthetic)),
+        }
+    }
+    kept.into_iter().map(|(_, impl_id, _)| impl_id).collect()
+}
+
+fn process_proc_macro_details(output: &mut String, proc_macro: &ProcMacro, level: usize) {
+    // Cap heading level at 6 (maximum valid Markdown heading level)
+    let heading_level = std::cmp::min(level, 6);
+
+    if proc_macro.kind == MacroKind::Derive && !proc_macro.helpers.is_empty() {
+        output.push_str(&format!(
+            "{} Helper Attributes\n\n",
+            "#".repeat(heading_level)
+        ));
+        for helper in &proc_macro.helpers {
+            output.push_str(&format!("- `#[{}]`\n", helper));
         }
         output.push('\n');
     }
 }
 
-fn process_impl_details(output: &mut String, impl_: &Impl, data: &Crate, level: usize) {
+fn process_impl_details(
+    output: &mut String,
+    impl_: &Impl,
+    data: &Crate,
+    options: &MarkdownOptions,
+    level: usize,
+) {
     // Cap heading level at 6 (maximum valid Markdown heading level)
     let heading_level = std::cmp::min(level, 6);
     // List all items in the impl
@@ -2193,8 +3575,12 @@ fn process_impl_details(output: &mut String, impl_: &Impl, data: &Crate, level:
         let mut assoc_types = Vec::new();
         let mut assoc_consts = Vec::new();
 
-        for &item_id in &impl_.items {
-            if let Some(item) = data.index.get(&item_id) {
+        for item_id in impl_items_in_order(impl_, data, options) {
+            if let Some(item) = data
+                .index
+                .get(&item_id)
+                .filter(|item| !is_hidden(item, options))
+            {
                 match &item.inner {
                     ItemEnum::Function(_) => methods.push(item_id),
                     ItemEnum::AssocType { .. } => assoc_types.push(item_id),
@@ -2210,7 +3596,13 @@ fn process_impl_details(output: &mut String, impl_: &Impl, data: &Crate, level:
                 "#".repeat(heading_level + 1)
             ));
             for &type_id in &assoc_types {
-                process_item(output, data.index.get(&type_id).unwrap(), data, level + 1);
+                process_item(
+                    output,
+                    data.index.get(&type_id).unwrap(),
+                    data,
+                    options,
+                    level + 1,
+                );
             }
         }
 
@@ -2220,14 +3612,26 @@ fn process_impl_details(output: &mut String, impl_: &Impl, data: &Crate, level:
                 "#".repeat(heading_level + 1)
             ));
             for &const_id in &assoc_consts {
-                process_item(output, data.index.get(&const_id).unwrap(), data, level + 1);
+                process_item(
+                    output,
+                    data.index.get(&const_id).unwrap(),
+                    data,
+                    options,
+                    level + 1,
+                );
             }
         }
 
         if !methods.is_empty() {
             output.push_str(&format!("{} Methods\n\n", "#".repeat(heading_level + 1)));
             for &method_id in &methods {
-                process_item(output, data.index.get(&method_id).unwrap(), data, level + 1);
+                process_item(
+                    output,
+                    data.index.get(&method_id).unwrap(),
+                    data,
+                    options,
+                    level + 1,
+                );
             }
         }
     }
@@ -2247,11 +3651,1551 @@ fn process_impl_details(output: &mut String, impl_: &Impl, data: &Crate, level:
         output.push('\n');
     }
 
-    // If this is a blanket impl, mention it
-    if let Some(blanket_type) = &impl_.blanket_impl {
+    // If this is a blanket impl, mention it along with the generics and where
+    // clause that decide which types it covers
+    if impl_.blanket_impl.is_some() {
+        let mut impl_header = String::new();
+        format_impl_header(&mut impl_header, impl_, data);
         output.push_str(&format!(
-            "This is a blanket implementation for all types that match: `{}`\n\n",
-            format_type(blanket_type, data)
+            "This is a blanket implementation for all types that match its bounds: `{}`\n\n",
+            impl_header.split_whitespace().collect::<Vec<_>>().join(" ")
+        ));
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::HashMap;
+
+    use rustdoc_types::{
+        AssocItemConstraint, Constant, Discriminant, DynTrait, FORMAT_VERSION, Function,
+        FunctionHeader, FunctionSignature, Module, Path, PolyTrait, Target, Variant,
+    };
+
+    use super::*;
+
+    /// Builds a crate named `demo` whose root module (id 0) lists `root_items`,
+    /// with `items` making up the rest of the index.
+    pub(crate) fn test_crate(root_items: &[u32], items: Vec<Item>) -> Crate {
+        let root = item(
+            0,
+            "demo",
+            ItemEnum::Module(Module {
+                is_crate: true,
+                items: root_items.iter().map(|&id| Id(id)).collect(),
+                is_stripped: false,
+            }),
+        );
+        let mut index: HashMap<Id, Item> = items.into_iter().map(|item| (item.id, item)).collect();
+        index.insert(root.id, root);
+
+        Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index,
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            target: Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: Vec::new(),
+            },
+            format_version: FORMAT_VERSION,
+        }
+    }
+
+    /// Builds a public, undocumented item.
+    pub(crate) fn item(id: u32, name: &str, inner: ItemEnum) -> Item {
+        Item {
+            id: Id(id),
+            crate_id: 0,
+            name: Some(name.to_string()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner,
+        }
+    }
+
+    /// Builds an item of a trait or impl, which rustdoc gives default visibility.
+    pub(crate) fn assoc_item(id: u32, name: &str, inner: ItemEnum) -> Item {
+        Item {
+            visibility: Visibility::Default,
+            ..item(id, name, inner)
+        }
+    }
+
+    pub(crate) fn documented(item: Item, docs: &str) -> Item {
+        Item {
+            docs: Some(docs.to_string()),
+            ..item
+        }
+    }
+
+    pub(crate) fn no_generics() -> Generics {
+        Generics {
+            params: Vec::new(),
+            where_predicates: Vec::new(),
+        }
+    }
+
+    pub(crate) fn primitive(name: &str) -> Type {
+        Type::Primitive(name.to_string())
+    }
+
+    pub(crate) fn resolved(path: &str, id: u32, args: Option<GenericArgs>) -> Type {
+        Type::ResolvedPath(item_path(path, id, args))
+    }
+
+    pub(crate) fn item_path(path: &str, id: u32, args: Option<GenericArgs>) -> Path {
+        Path {
+            path: path.to_string(),
+            id: Id(id),
+            args: args.map(Box::new),
+        }
+    }
+
+    pub(crate) fn struct_with_fields(fields: &[u32], impls: &[u32]) -> ItemEnum {
+        ItemEnum::Struct(Struct {
+            kind: StructKind::Plain {
+                fields: fields.iter().map(|&id| Id(id)).collect(),
+                has_stripped_fields: false,
+            },
+            generics: no_generics(),
+            impls: impls.iter().map(|&id| Id(id)).collect(),
+        })
+    }
+
+    pub(crate) fn trait_with_items(items: &[u32], implementations: &[u32]) -> ItemEnum {
+        ItemEnum::Trait(Trait {
+            is_auto: false,
+            is_unsafe: false,
+            is_dyn_compatible: true,
+            items: items.iter().map(|&id| Id(id)).collect(),
+            generics: no_generics(),
+            bounds: Vec::new(),
+            implementations: implementations.iter().map(|&id| Id(id)).collect(),
+        })
+    }
+
+    pub(crate) fn impl_block(trait_: Option<Path>, for_: Type, items: &[u32]) -> Impl {
+        Impl {
+            is_unsafe: false,
+            generics: no_generics(),
+            provided_trait_methods: Vec::new(),
+            trait_,
+            for_,
+            items: items.iter().map(|&id| Id(id)).collect(),
+            is_negative: false,
+            is_synthetic: false,
+            blanket_impl: None,
+        }
+    }
+
+    /// Builds an unnamed impl item, as rustdoc emits them.
+    pub(crate) fn impl_item(id: u32, trait_: Option<Path>, for_: Type, items: &[u32]) -> Item {
+        Item {
+            name: None,
+            ..assoc_item(id, "", ItemEnum::Impl(impl_block(trait_, for_, items)))
+        }
+    }
+
+    pub(crate) fn function(inputs: Vec<(&str, Type)>, output: Option<Type>) -> ItemEnum {
+        ItemEnum::Function(Function {
+            sig: FunctionSignature {
+                inputs: inputs
+                    .into_iter()
+                    .map(|(name, type_)| (name.to_string(), type_))
+                    .collect(),
+                output,
+                is_c_variadic: false,
+            },
+            generics: no_generics(),
+            header: FunctionHeader {
+                is_const: false,
+                is_unsafe: false,
+                is_async: false,
+                abi: Abi::Rust,
+            },
+            has_body: true,
+        })
+    }
+
+    pub(crate) fn trait_bound(path: Path) -> GenericBound {
+        GenericBound::TraitBound {
+            trait_: path,
+            generic_params: Vec::new(),
+            modifier: TraitBoundModifier::None,
+        }
+    }
+
+    /// Builds a `where` predicate bounding the generic `param` by `bounds`.
+    pub(crate) fn bound_predicate(param: &str, bounds: &[&str]) -> WherePredicate {
+        WherePredicate::BoundPredicate {
+            type_: Type::Generic(param.to_string()),
+            bounds: bounds
+                .iter()
+                .map(|bound| trait_bound(item_path(bound, 100, None)))
+                .collect(),
+            generic_params: Vec::new(),
+        }
+    }
+
+    /// Builds `<Name = type_>` args.
+    pub(crate) fn binding(name: &str, type_: Type) -> GenericArgs {
+        GenericArgs::AngleBracketed {
+            args: Vec::new(),
+            constraints: vec![AssocItemConstraint {
+                name: name.to_string(),
+                args: None,
+                binding: AssocItemConstraintKind::Equality(Term::Type(type_)),
+            }],
+        }
+    }
+
+    /// Formats an item's signature with the default options.
+    pub(crate) fn signature(item: &Item, data: &Crate) -> String {
+        let mut output = String::new();
+        format_item_signature(&mut output, item, data, &MarkdownOptions::default());
+        output
+    }
+
+    pub(crate) fn render(data: &Crate) -> String {
+        MarkdownOptions::default().render(data)
+    }
+
+    #[test]
+    fn pipes_in_field_docs_stay_inside_their_table_cell() {
+        let data = test_crate(
+            &[1],
+            vec![
+                item(1, "Flags", struct_with_fields(&[2], &[])),
+                documented(
+                    item(2, "mode", ItemEnum::StructField(primitive("u8"))),
+                    "Either `a | b` or `c`.\nDefaults to `a`.",
+                ),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains("| `mode` | `u8` | Either `a \\| b` or `c`. |\n"),
+            "{}",
+            output
+        );
+
+        let options = MarkdownOptions::new().table_doc_mode(TableDocMode::Escaped);
+        assert!(
+            options
+                .render(&data)
+                .contains("| `mode` | `u8` | Either `a \\| b` or `c`. Defaults to `a`. |\n")
+        );
+
+        let options = MarkdownOptions::new().table_doc_mode(TableDocMode::HtmlBreaks);
+        assert!(
+            options
+                .render(&data)
+                .contains("| `mode` | `u8` | Either `a \\| b` or `c`.<br>Defaults to `a`. |\n")
+        );
+    }
+
+    #[test]
+    fn diagram_blocks_pass_through_doc_processing_untouched() {
+        let docs = "# Flow\n\n```mermaid\ngraph TD\n# not a heading\n    A --> B\n```";
+        let options = MarkdownOptions::new().strip_doc_tests(true);
+        assert_eq!(
+            render_docs(docs, 3, &options),
+            "#### Flow\n\n```mermaid\ngraph TD\n# not a heading\n    A --> B\n```"
+        );
+
+        let options = options.diagram_blocks(DiagramBlocks::Label);
+        assert_eq!(
+            render_docs(docs, 3, &options),
+            "#### Flow\n\n*Diagram (mermaid):*\n\n```mermaid\ngraph TD\n# not a heading\n    A --> B\n```"
+        );
+    }
+
+    #[test]
+    fn trait_implementors_render_in_the_same_order_regardless_of_input_order() {
+        let greet = || item_path("Greet", 1, None);
+        let mut data = test_crate(
+            &[1, 2, 3, 4],
+            vec![
+                item(1, "Greet", trait_with_items(&[], &[10, 11, 12])),
+                item(2, "Zeta", struct_with_fields(&[], &[10])),
+                item(3, "Alpha", struct_with_fields(&[], &[11])),
+                item(4, "Mid", struct_with_fields(&[], &[12])),
+                impl_item(10, Some(greet()), resolved("Zeta", 2, None), &[]),
+                impl_item(11, Some(greet()), resolved("Alpha", 3, None), &[]),
+                impl_item(12, Some(greet()), resolved("Mid", 4, None), &[]),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains(
+                "- `impl Greet for Alpha`\n- `impl Greet for Mid`\n- `impl Greet for Zeta`\n"
+            ),
+            "{}",
+            output
+        );
+
+        if let ItemEnum::Trait(trait_) = &mut data.index.get_mut(&Id(1)).unwrap().inner {
+            trait_.implementations.reverse();
+        }
+        assert_eq!(render(&data), output);
+    }
+
+    #[test]
+    fn computed_discriminants_show_their_expression_and_value() {
+        let variant = |id, name, expr: &str, value: &str| {
+            item(
+                id,
+                name,
+                ItemEnum::Variant(Variant {
+                    kind: VariantKind::Plain,
+                    discriminant: Some(Discriminant {
+                        expr: expr.to_string(),
+                        value: value.to_string(),
+                    }),
+                }),
+            )
+        };
+        let data = test_crate(
+            &[1],
+            vec![
+                item(
+                    1,
+                    "Flag",
+                    ItemEnum::Enum(Enum {
+                        generics: no_generics(),
+                        has_stripped_variants: false,
+                        variants: vec![Id(2), Id(3)],
+                        impls: Vec::new(),
+                    }),
+                ),
+                variant(2, "Shifted", "1 << 3", "8"),
+                variant(3, "Plain", "2", "2"),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains("##### `Shifted`\n\nDiscriminant expression: `1 << 3` (value `8`)\n"),
+            "{}",
+            output
+        );
+        assert!(output.contains("##### `Plain`\n\nDiscriminant value: `2`\n"));
+    }
+
+    #[test]
+    fn associated_consts_render_types_that_use_trait_generics() {
+        let mut trait_ = trait_with_items(&[2], &[]);
+        if let ItemEnum::Trait(trait_) = &mut trait_ {
+            trait_.generics.params.push(GenericParamDef {
+                name: "M".to_string(),
+                kind: GenericParamDefKind::Const {
+                    type_: primitive("usize"),
+                    default: None,
+                },
+            });
+        }
+        let data = test_crate(
+            &[1],
+            vec![
+                item(1, "Buffer", trait_),
+                assoc_item(
+                    2,
+                    "BYTES",
+                    ItemEnum::AssocConst {
+                        type_: Type::Array {
+                            type_: Box::new(primitive("u8")),
+                            len: "M".to_string(),
+                        },
+                        value: None,
+                    },
+                ),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains("pub trait Buffer<const M: usize> {"),
+            "{}",
+            output
+        );
+        assert!(output.contains("- `const BYTES: [u8; M];`\n"), "{}", output);
+    }
+
+    #[test]
+    fn impl_trait_bounds_list_the_primary_trait_before_auto_traits_and_lifetimes() {
+        let data = test_crate(&[], Vec::new());
+        let future = || {
+            trait_bound(item_path(
+                "Future",
+                100,
+                Some(binding("Output", primitive("u32"))),
+            ))
+        };
+        let bounds = vec![
+            GenericBound::Outlives("'a".to_string()),
+            trait_bound(item_path("Send", 101, None)),
+            future(),
+        ];
+        let fetch = item(
+            1,
+            "fetch",
+            function(Vec::new(), Some(Type::ImplTrait(bounds))),
+        );
+        assert_eq!(
+            signature(&fetch, &data),
+            "pub fn fetch() -> impl Future<Output = u32> + Send + 'a { /* ... */ }"
+        );
+
+        // `async fn fetch() -> u32`, with the return type desugared
+        let mut fetch = item(
+            1,
+            "fetch",
+            function(Vec::new(), Some(Type::ImplTrait(vec![future()]))),
+        );
+        if let ItemEnum::Function(function) = &mut fetch.inner {
+            function.header.is_async = true;
+        }
+        assert_eq!(
+            signature(&fetch, &data),
+            "pub async fn fetch() -> u32 { /* ... */ }"
+        );
+    }
+
+    #[test]
+    fn self_projections_in_trait_methods_render_without_qualified_path_brackets() {
+        // rustdoc encodes `Self::Output` in the trait's own methods with an empty
+        // trait path
+        let output = Type::QualifiedPath {
+            name: "Output".to_string(),
+            args: None,
+            self_type: Box::new(Type::Generic("Self".to_string())),
+            trait_: Some(item_path("", 1, None)),
+        };
+        let mut add = assoc_item(
+            2,
+            "add",
+            function(
+                vec![
+                    ("self", Type::Generic("Self".to_string())),
+                    ("rhs", Type::Generic("Rhs".to_string())),
+                ],
+                Some(output),
+            ),
+        );
+        if let ItemEnum::Function(function) = &mut add.inner {
+            function.has_body = false;
+        }
+        let data = test_crate(&[], Vec::new());
+
+        assert_eq!(
+            signature(&add, &data),
+            "fn add(self, rhs: Rhs) -> Self::Output;"
+        );
+    }
+
+    #[test]
+    fn complex_const_generic_args_are_wrapped_in_braces() {
+        let data = test_crate(&[], Vec::new());
+        let foo = |expr: &str| {
+            resolved(
+                "Foo",
+                1,
+                Some(GenericArgs::AngleBracketed {
+                    args: vec![GenericArg::Const(Constant {
+                        expr: expr.to_string(),
+                        value: None,
+                        is_literal: false,
+                    })],
+                    constraints: Vec::new(),
+                }),
+            )
+        };
+
+        assert_eq!(format_type(&foo("3"), &data), "Foo<3>");
+        assert_eq!(format_type(&foo("N"), &data), "Foo<N>");
+        assert_eq!(format_type(&foo("N * 2"), &data), "Foo<{ N * 2 }>");
+        assert_eq!(format_type(&foo("{ N + 1 }"), &data), "Foo<{ N + 1 }>");
+    }
+
+    #[test]
+    fn tuple_struct_fields_are_separated_around_private_fields() {
+        let tuple = item(
+            1,
+            "Triple",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Tuple(vec![Some(Id(2)), None, Some(Id(3))]),
+                generics: no_generics(),
+                impls: Vec::new(),
+            }),
+        );
+        let data = test_crate(
+            &[1],
+            vec![
+                tuple.clone(),
+                item(2, "0", ItemEnum::StructField(primitive("u8"))),
+                item(3, "2", ItemEnum::StructField(primitive("bool"))),
+            ],
+        );
+
+        assert_eq!(
+            signature(&tuple, &data),
+            "pub struct Triple(pub u8, /* private field */, pub bool);"
+        );
+
+        let mut pair = tuple;
+        pair.inner = ItemEnum::Struct(Struct {
+            kind: StructKind::Tuple(vec![Some(Id(2)), None]),
+            generics: no_generics(),
+            impls: Vec::new(),
+        });
+        assert_eq!(
+            signature(&pair, &data),
+            "pub struct Triple(pub u8, /* private field */);"
+        );
+    }
+
+    #[test]
+    fn where_clauses_render_the_same_regardless_of_predicate_order() {
+        let data = test_crate(&[], Vec::new());
+        let mut predicates = vec![
+            bound_predicate("U", &["Debug"]),
+            bound_predicate("T", &["Clone", "Send"]),
+        ];
+
+        let mut first = String::from("fn pair<T, U>(t: T, u: U)");
+        format_where_clause(&mut first, &predicates, &data);
+        predicates.reverse();
+        let mut second = String::from("fn pair<T, U>(t: T, u: U)");
+        format_where_clause(&mut second, &predicates, &data);
+
+        assert_eq!(first, second);
+        assert_eq!(
+            first,
+            "fn pair<T, U>(t: T, u: U)\nwhere\n    T: Clone + Send,\n    U: Debug"
+        );
+    }
+
+    #[test]
+    fn strip_doc_tests_drops_hidden_lines_from_rust_code_blocks() {
+        let docs = "```\n# use std::collections::HashMap;\n#\nlet map: HashMap<u8, u8> = HashMap::new();\n## not hidden\n```\n\n```text\n# kept\n```";
+
+        let options = MarkdownOptions::new().strip_doc_tests(true);
+        assert_eq!(
+            render_docs(docs, 2, &options),
+            "```\nlet map: HashMap<u8, u8> = HashMap::new();\n# not hidden\n```\n\n```text\n# kept\n```"
+        );
+        assert_eq!(render_docs(docs, 2, &MarkdownOptions::default()), docs);
+    }
+
+    #[test]
+    fn async_fn_returns_render_in_sugar_form_for_both_encodings() {
+        let data = test_crate(&[], Vec::new());
+        let async_fn = |output: Option<Type>| {
+            let mut fetch = item(1, "fetch", function(Vec::new(), output));
+            if let ItemEnum::Function(function) = &mut fetch.inner {
+                function.header.is_async = true;
+            }
+            fetch
+        };
+        let future_of = |output: Type| {
+            Type::ImplTrait(vec![trait_bound(item_path(
+                "core::future::Future",
+                100,
+                Some(binding("Output", output)),
+            ))])
+        };
+
+        let sugared = async_fn(Some(primitive("u32")));
+        let desugared = async_fn(Some(future_of(primitive("u32"))));
+        assert_eq!(
+            signature(&sugared, &data),
+            "pub async fn fetch() -> u32 { /* ... */ }"
+        );
+        assert_eq!(signature(&desugared, &data), signature(&sugared, &data));
+
+        let unit = async_fn(Some(future_of(Type::Tuple(Vec::new()))));
+        assert_eq!(
+            signature(&unit, &data),
+            "pub async fn fetch() { /* ... */ }"
+        );
+    }
+
+    #[test]
+    fn negative_and_unsafe_impls_are_marked_in_implementation_lists() {
+        let marker = || item_path("Marker", 1, None);
+        let mut negative = impl_item(10, Some(marker()), resolved("Handle", 2, None), &[]);
+        let mut unsafe_ = impl_item(11, Some(marker()), resolved("Shared", 3, None), &[]);
+        if let ItemEnum::Impl(impl_) = &mut negative.inner {
+            impl_.is_negative = true;
+        }
+        if let ItemEnum::Impl(impl_) = &mut unsafe_.inner {
+            impl_.is_unsafe = true;
+        }
+        let data = test_crate(
+            &[1, 2, 3],
+            vec![
+                item(1, "Marker", trait_with_items(&[], &[10, 11])),
+                item(2, "Handle", struct_with_fields(&[], &[10])),
+                item(3, "Shared", struct_with_fields(&[], &[11])),
+                negative,
+                unsafe_,
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains("- `impl !Marker for Handle`\n- `unsafe impl Marker for Shared`\n"),
+            "{}",
+            output
+        );
+        assert!(output.contains("- **`!Marker`**\n"), "{}", output);
+        assert!(output.contains("- **`unsafe Marker`**\n"), "{}", output);
+    }
+
+    #[test]
+    fn trait_methods_returning_self_render_self() {
+        let mut new = assoc_item(
+            2,
+            "new",
+            function(Vec::new(), Some(Type::Generic("Self".to_string()))),
+        );
+        if let ItemEnum::Function(function) = &mut new.inner {
+            function.has_body = false;
+        }
+        let data = test_crate(
+            &[1],
+            vec![item(1, "Create", trait_with_items(&[2], &[])), new],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains("##### Required Methods\n\n- ```rust\n  fn new() -> Self;\n  ```\n"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn output_budget_notes_truncation_only_when_items_are_left_out() {
+        let data = test_crate(
+            &[1, 2],
+            vec![
+                item(1, "First", struct_with_fields(&[], &[])),
+                item(2, "Second", struct_with_fields(&[], &[])),
+            ],
+        );
+        let full = render(&data);
+
+        // Crossing the budget with the last item leaves nothing out
+        let options = MarkdownOptions::new().max_output_bytes(Some(full.len() - 20));
+        assert_eq!(options.render(&data), full);
+
+        let options = MarkdownOptions::new().max_output_bytes(Some(1));
+        let output = options.render(&data);
+        assert!(output.contains(OUTPUT_TRUNCATED_NOTE), "{}", output);
+        assert!(!output.contains("First"), "{}", output);
+    }
+
+    #[test]
+    fn generics_with_only_synthetic_or_elided_params_render_no_brackets() {
+        let data = test_crate(&[], Vec::new());
+        let generics = Generics {
+            params: vec![
+                GenericParamDef {
+                    name: "'_".to_string(),
+                    kind: GenericParamDefKind::Lifetime {
+                        outlives: Vec::new(),
+                    },
+                },
+                GenericParamDef {
+                    name: "impl Display".to_string(),
+                    kind: GenericParamDefKind::Type {
+                        bounds: Vec::new(),
+                        default: None,
+                        is_synthetic: true,
+                    },
+                },
+            ],
+            where_predicates: Vec::new(),
+        };
+
+        let mut output = String::new();
+        format_generics(&mut output, &generics, &data);
+        assert_eq!(output, "");
+
+        let impl_ = Impl {
+            generics,
+            ..impl_block(Some(item_path("Greet", 2, None)), primitive("u8"), &[])
+        };
+        let mut header = String::new();
+        format_impl_header(&mut header, &impl_, &data);
+        assert_eq!(header, "impl Greet for u8");
+    }
+
+    #[test]
+    fn dyn_traits_keep_associated_type_bindings_and_extra_bounds() {
+        let data = test_crate(&[], Vec::new());
+        let poly_trait = |path: Path| PolyTrait {
+            trait_: path,
+            generic_params: Vec::new(),
+        };
+        let dyn_iterator = |lifetime: Option<&str>| {
+            Type::DynTrait(DynTrait {
+                traits: vec![
+                    poly_trait(item_path(
+                        "Iterator",
+                        100,
+                        Some(binding("Item", primitive("u8"))),
+                    )),
+                    poly_trait(item_path("Send", 101, None)),
+                ],
+                lifetime: lifetime.map(str::to_string),
+            })
+        };
+        let boxed = |inner: Type| {
+            resolved(
+                "Box",
+                102,
+                Some(GenericArgs::AngleBracketed {
+                    args: vec![GenericArg::Type(inner)],
+                    constraints: Vec::new(),
+                }),
+            )
+        };
+
+        assert_eq!(
+            format_type(&boxed(dyn_iterator(None)), &data),
+            "Box<dyn Iterator<Item = u8> + Send>"
+        );
+        assert_eq!(
+            format_type(&boxed(dyn_iterator(Some("'a"))), &data),
+            "Box<dyn Iterator<Item = u8> + Send + 'a>"
+        );
+    }
+
+    #[test]
+    fn parenthesized_fn_bounds_keep_their_return_type() {
+        let data = test_crate(&[], Vec::new());
+        let fn_path = |name: &str, inputs: Vec<Type>, output: Option<Type>| {
+            item_path(
+                name,
+                100,
+                Some(GenericArgs::Parenthesized { inputs, output }),
+            )
+        };
+
+        let predicate = WherePredicate::BoundPredicate {
+            type_: Type::Generic("F".to_string()),
+            bounds: vec![trait_bound(fn_path(
+                "Fn",
+                vec![primitive("u32")],
+                Some(resolved("String", 101, None)),
+            ))],
+            generic_params: Vec::new(),
+        };
+        let mut output = String::from("fn map<F>(f: F)");
+        format_where_clause(&mut output, &[predicate], &data);
+        assert_eq!(output, "fn map<F>(f: F) where F: Fn(u32) -> String");
+
+        let dyn_fn_mut = Type::DynTrait(DynTrait {
+            traits: vec![
+                PolyTrait {
+                    trait_: fn_path("FnMut", Vec::new(), None),
+                    generic_params: Vec::new(),
+                },
+                PolyTrait {
+                    trait_: item_path("Send", 102, None),
+                    generic_params: Vec::new(),
+                },
+            ],
+            lifetime: None,
+        });
+        assert_eq!(format_type(&dyn_fn_mut, &data), "dyn FnMut() + Send");
+        let borrowed = Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: true,
+            type_: Box::new(dyn_fn_mut),
+        };
+        assert_eq!(format_type(&borrowed, &data), "&mut (dyn FnMut() + Send)");
+    }
+
+    #[test]
+    fn higher_ranked_bounds_render_their_binder() {
+        let data = test_crate(&[], Vec::new());
+        let lifetime_a = || {
+            vec![GenericParamDef {
+                name: "'a".to_string(),
+                kind: GenericParamDefKind::Lifetime {
+                    outlives: Vec::new(),
+                },
+            }]
+        };
+        let str_ref = || Type::BorrowedRef {
+            lifetime: Some("'a".to_string()),
+            is_mutable: false,
+            type_: Box::new(primitive("str")),
+        };
+        let fn_path = || {
+            item_path(
+                "Fn",
+                100,
+                Some(GenericArgs::Parenthesized {
+                    inputs: vec![str_ref()],
+                    output: Some(str_ref()),
+                }),
+            )
+        };
+
+        let bound = GenericBound::TraitBound {
+            trait_: fn_path(),
+            generic_params: lifetime_a(),
+            modifier: TraitBoundModifier::None,
+        };
+        let mut output = String::new();
+        format_bounds(&mut output, std::slice::from_ref(&bound), &data);
+        assert_eq!(output, "for<'a> Fn(&'a str) -> &'a str");
+
+        let mut output = String::from("fn apply<F>(f: F)");
+        let predicate = WherePredicate::BoundPredicate {
+            type_: Type::Generic("F".to_string()),
+            bounds: vec![trait_bound(fn_path())],
+            generic_params: lifetime_a(),
+        };
+        format_where_clause(&mut output, &[predicate], &data);
+        assert_eq!(
+            output,
+            "fn apply<F>(f: F) where for<'a> F: Fn(&'a str) -> &'a str"
+        );
+    }
+
+    #[test]
+    fn list_indent_applies_to_every_nested_method_listing() {
+        let self_ref = || Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: false,
+            type_: Box::new(Type::Generic("Self".to_string())),
+        };
+        let data = test_crate(
+            &[1],
+            vec![
+                item(1, "Point", struct_with_fields(&[], &[10, 11])),
+                impl_item(10, None, resolved("Point", 1, None), &[12]),
+                impl_item(
+                    11,
+                    Some(item_path("Clone", 100, None)),
+                    resolved("Point", 1, None),
+                    &[13],
+                ),
+                documented(
+                    item(
+                        12,
+                        "len",
+                        function(vec![("self", self_ref())], Some(primitive("f64"))),
+                    ),
+                    "Returns the distance from the origin.",
+                ),
+                assoc_item(
+                    13,
+                    "clone",
+                    function(
+                        vec![("self", self_ref())],
+                        Some(Type::Generic("Self".to_string())),
+                    ),
+                ),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains(
+                "- ```rust\n  pub fn len(&self) -> f64 { /* ... */ }\n  ```\n  Returns the distance from the origin.\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(output.contains(
+            "- **`Clone`**\n  - ```rust\n    fn clone(&self) -> Self { /* ... */ }\n    ```\n"
         ));
+
+        let output = MarkdownOptions::new().list_indent(4).render(&data);
+        assert!(
+            output.contains(
+                "- ```rust\n    pub fn len(&self) -> f64 { /* ... */ }\n    ```\n    Returns the distance from the origin.\n"
+            ),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains(
+                "- **`Clone`**\n    - ```rust\n        fn clone(&self) -> Self { /* ... */ }\n        ```\n"
+            ),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn duplicate_impls_render_once_keeping_the_real_one() {
+        let marker = || Some(item_path("Marker", 1, None));
+        let point = || resolved("Point", 2, None);
+        let synthetic = Item {
+            name: None,
+            ..item(
+                10,
+                "",
+                ItemEnum::Impl(Impl {
+                    is_synthetic: true,
+                    ..impl_block(marker(), point(), &[])
+                }),
+            )
+        };
+        let real = documented(impl_item(11, marker(), point(), &[]), "Written by hand.");
+        let data = test_crate(
+            &[1, 2],
+            vec![
+                item(1, "Marker", trait_with_items(&[], &[10, 11])),
+                item(2, "Point", struct_with_fields(&[], &[10, 11])),
+                synthetic,
+                real,
+            ],
+        );
+
+        let output = render(&data);
+        assert_eq!(
+            output.matches("- `impl Marker for Point`").count(),
+            1,
+            "{}",
+            output
+        );
+        assert!(output.contains("- `impl Marker for Point`: Written by hand.\n"));
+        assert_eq!(output.matches("- **`Marker`**").count(), 1, "{}", output);
+        assert_eq!(dedup_impls(&[Id(10), Id(11)], &data), vec![Id(11)]);
+    }
+
+    #[test]
+    fn impl_associated_consts_render_their_value() {
+        let max = |id, value: Option<&str>| {
+            assoc_item(
+                id,
+                "MAX",
+                ItemEnum::AssocConst {
+                    type_: primitive("usize"),
+                    value: value.map(str::to_string),
+                },
+            )
+        };
+        let impl_ = impl_block(
+            Some(item_path("Bounded", 1, None)),
+            resolved("Buffer", 2, None),
+            &[11],
+        );
+        let data = test_crate(
+            &[1, 2],
+            vec![
+                item(1, "Bounded", trait_with_items(&[10], &[])),
+                item(2, "Buffer", struct_with_fields(&[], &[])),
+                max(10, None),
+                max(11, Some("100")),
+            ],
+        );
+
+        assert!(render(&data).contains("- `const MAX: usize;`\n"));
+
+        let mut output = String::new();
+        process_impl_details(&mut output, &impl_, &data, &MarkdownOptions::default(), 3);
+        assert!(
+            output.contains("```rust\nconst MAX: usize = 100;\n```"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn pattern_types_render_inside_a_comment() {
+        // As nightly rustdoc encodes `fn clamp(x: pattern_type!(u32 is 1..=10))`
+        let pattern_type = Type::Pat {
+            type_: Box::new(primitive("u32")),
+            __pat_unstable_do_not_use: "1..=10".to_string(),
+        };
+        let clamp = item(1, "clamp", function(vec![("x", pattern_type)], None));
+        let data = test_crate(&[1], vec![clamp.clone()]);
+
+        assert_eq!(
+            signature(&clamp, &data),
+            "pub fn clamp(x: /* pattern type: u32 is 1..=10 */) { /* ... */ }"
+        );
+    }
+
+    #[test]
+    fn preserved_module_depth_nests_module_headings_too() {
+        let module = |id, name, items: &[u32]| {
+            item(
+                id,
+                name,
+                ItemEnum::Module(Module {
+                    is_crate: false,
+                    items: items.iter().map(|&id| Id(id)).collect(),
+                    is_stripped: false,
+                }),
+            )
+        };
+        let data = test_crate(
+            &[1],
+            vec![
+                module(1, "a", &[2]),
+                module(2, "b", &[3]),
+                item(3, "Leaf", struct_with_fields(&[], &[])),
+            ],
+        );
+
+        let headings = |output: &str| -> Vec<String> {
+            output
+                .lines()
+                .filter(|line| line.starts_with('#'))
+                .map(str::to_string)
+                .collect()
+        };
+
+        let output = MarkdownOptions::new()
+            .preserve_module_depth(true)
+            .render(&data);
+        assert_eq!(
+            headings(&output)[1..7],
+            [
+                "## Modules",
+                "### Module `a`",
+                "#### Modules",
+                "##### Module `b`",
+                "###### Types",
+                "###### Struct `Leaf`",
+            ]
+        );
+
+        assert_eq!(
+            headings(&render(&data))[1..7],
+            [
+                "## Modules",
+                "## Module `a`",
+                "### Modules",
+                "## Module `b`",
+                "### Types",
+                "#### Struct `Leaf`",
+            ]
+        );
+    }
+
+    #[test]
+    fn bare_generic_projections_render_without_qualified_path_brackets() {
+        let data = test_crate(&[], Vec::new());
+        // rustdoc's encoding of `I::Item` and `<I as IntoIterator>::Item`
+        let item_of = |trait_name: &str| Type::QualifiedPath {
+            name: "Item".to_string(),
+            args: None,
+            self_type: Box::new(Type::Generic("I".to_string())),
+            trait_: Some(item_path(trait_name, 100, None)),
+        };
+
+        assert_eq!(format_type(&item_of(""), &data), "I::Item");
+        assert_eq!(
+            format_type(&item_of("IntoIterator"), &data),
+            "<I as IntoIterator>::Item"
+        );
+    }
+
+    #[test]
+    fn return_type_notation_renders_after_the_method_name() {
+        let data = test_crate(&[], Vec::new());
+        let send = || vec![trait_bound(item_path("Send", 101, None))];
+
+        // `fn spawn<S: Svc<call(..): Send>>(s: S)`
+        let constrained = Generics {
+            params: vec![GenericParamDef {
+                name: "S".to_string(),
+                kind: GenericParamDefKind::Type {
+                    bounds: vec![trait_bound(item_path(
+                        "Svc",
+                        100,
+                        Some(GenericArgs::AngleBracketed {
+                            args: Vec::new(),
+                            constraints: vec![AssocItemConstraint {
+                                name: "call".to_string(),
+                                args: Some(Box::new(GenericArgs::ReturnTypeNotation)),
+                                binding: AssocItemConstraintKind::Constraint(send()),
+                            }],
+                        }),
+                    ))],
+                    default: None,
+                    is_synthetic: false,
+                },
+            }],
+            where_predicates: Vec::new(),
+        };
+        let mut output = String::new();
+        format_generics(&mut output, &constrained, &data);
+        assert_eq!(output, "<S: Svc<call(..): Send>>");
+
+        // `where S::call(..): Send`
+        let predicate = WherePredicate::BoundPredicate {
+            type_: Type::QualifiedPath {
+                name: "call".to_string(),
+                args: Some(Box::new(GenericArgs::ReturnTypeNotation)),
+                self_type: Box::new(Type::Generic("S".to_string())),
+                trait_: Some(item_path("", 100, None)),
+            },
+            bounds: send(),
+            generic_params: Vec::new(),
+        };
+        let mut output = String::from("fn spawn<S: Svc>(s: S)");
+        format_where_clause(&mut output, &[predicate], &data);
+        assert_eq!(output, "fn spawn<S: Svc>(s: S) where S::call(..): Send");
+    }
+
+    #[test]
+    fn inherent_impl_docs_render_ahead_of_their_methods() {
+        let data = test_crate(
+            &[1],
+            vec![
+                item(1, "Point", struct_with_fields(&[], &[10])),
+                documented(
+                    impl_item(10, None, resolved("Point", 1, None), &[11]),
+                    "Constructors for points.",
+                ),
+                item(
+                    11,
+                    "origin",
+                    function(Vec::new(), Some(resolved("Point", 1, None))),
+                ),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains(
+                "Constructors for points.\n\n- ```rust\n  pub fn origin() -> Point { /* ... */ }\n  ```\n"
+            ),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn c_variadic_functions_end_their_parameters_with_an_ellipsis() {
+        let data = test_crate(&[], Vec::new());
+        let variadic = |inputs| {
+            let ItemEnum::Function(function) = function(inputs, Some(primitive("i32"))) else {
+                unreachable!()
+            };
+            ItemEnum::Function(Function {
+                sig: FunctionSignature {
+                    is_c_variadic: true,
+                    ..function.sig
+                },
+                header: FunctionHeader {
+                    is_unsafe: true,
+                    abi: Abi::C { unwind: false },
+                    ..function.header
+                },
+                has_body: false,
+                ..function
+            })
+        };
+        let c_char = Type::RawPointer {
+            is_mutable: false,
+            type_: Box::new(resolved("c_char", 100, None)),
+        };
+
+        let printf = item(1, "printf", variadic(vec![("fmt", c_char)]));
+        assert_eq!(
+            signature(&printf, &data),
+            "extern \"C\" {\n    pub unsafe fn printf(fmt: *const c_char, ...) -> i32;\n}"
+        );
+        let any = item(2, "any", variadic(Vec::new()));
+        assert_eq!(
+            signature(&any, &data),
+            "extern \"C\" {\n    pub unsafe fn any(...) -> i32;\n}"
+        );
+    }
+
+    #[test]
+    fn doc_headings_nest_under_their_item_heading() {
+        let docs = "Parses input.\n\n# Examples\n\n```\n# use demo::parse;\n```\n\n## Errors";
+        let data = test_crate(
+            &[1],
+            vec![documented(
+                item(1, "parse", function(Vec::new(), None)),
+                docs,
+            )],
+        );
+
+        let output = render(&data);
+        assert!(output.contains("### Function `parse`\n"), "{}", output);
+        assert!(output.contains("\n#### Examples\n"), "{}", output);
+        assert!(output.contains("\n##### Errors"), "{}", output);
+        assert!(output.contains("# use demo::parse;"), "{}", output);
+
+        assert_eq!(
+            nest_heading("# Panics", 5),
+            Some("###### Panics".to_string())
+        );
+        assert_eq!(nest_heading("#hashtag", 2), None);
+        assert_eq!(nest_heading("    # indented code", 2), None);
+    }
+
+    #[test]
+    fn qualified_paths_keep_generic_self_types_and_trait_args() {
+        let data = test_crate(&[], Vec::new());
+
+        let into_iter = Type::QualifiedPath {
+            name: "IntoIter".to_string(),
+            args: None,
+            self_type: Box::new(resolved(
+                "Vec",
+                100,
+                Some(GenericArgs::AngleBracketed {
+                    args: vec![GenericArg::Type(primitive("u8"))],
+                    constraints: Vec::new(),
+                }),
+            )),
+            trait_: Some(item_path("IntoIterator", 101, None)),
+        };
+        assert_eq!(
+            format_type(&into_iter, &data),
+            "<Vec<u8> as IntoIterator>::IntoIter"
+        );
+
+        let output = Type::QualifiedPath {
+            name: "Output".to_string(),
+            args: None,
+            self_type: Box::new(Type::Generic("T".to_string())),
+            trait_: Some(item_path(
+                "Trait",
+                102,
+                Some(GenericArgs::AngleBracketed {
+                    args: vec![
+                        GenericArg::Lifetime("'a".to_string()),
+                        GenericArg::Type(Type::Generic("A".to_string())),
+                    ],
+                    constraints: Vec::new(),
+                }),
+            )),
+        };
+        assert_eq!(format_type(&output, &data), "<T as Trait<'a, A>>::Output");
+    }
+
+    #[test]
+    fn single_short_where_predicates_stay_on_the_signature_line() {
+        let data = test_crate(&[], Vec::new());
+
+        let mut output = String::from("fn show<T>(t: T)");
+        format_where_clause(&mut output, &[bound_predicate("T", &["Display"])], &data);
+        assert_eq!(output, "fn show<T>(t: T) where T: Display");
+
+        let long_bounds = [
+            "Display",
+            "Debug",
+            "Clone",
+            "Send",
+            "Sync",
+            "PartialOrd",
+            "Hash",
+        ];
+        let mut output = String::from("fn show_everything<T>(t: T)");
+        format_where_clause(&mut output, &[bound_predicate("T", &long_bounds)], &data);
+        assert_eq!(
+            output,
+            "fn show_everything<T>(t: T)\nwhere\n    T: Display + Debug + Clone + Send + Sync + PartialOrd + Hash"
+        );
+
+        let mut output = String::from("fn show<T, U>(t: T, u: U)");
+        format_where_clause(
+            &mut output,
+            &[
+                bound_predicate("T", &["Display"]),
+                bound_predicate("U", &["Debug"]),
+            ],
+            &data,
+        );
+        assert_eq!(
+            output,
+            "fn show<T, U>(t: T, u: U)\nwhere\n    T: Display,\n    U: Debug"
+        );
+    }
+
+    #[test]
+    fn generic_param_defaults_render_after_their_bounds() {
+        let data = test_crate(&[], Vec::new());
+        let type_param = |name: &str, default| GenericParamDef {
+            name: name.to_string(),
+            kind: GenericParamDefKind::Type {
+                bounds: Vec::new(),
+                default,
+                is_synthetic: false,
+            },
+        };
+        let const_param = |name: &str, default: &str| GenericParamDef {
+            name: name.to_string(),
+            kind: GenericParamDefKind::Const {
+                type_: primitive("usize"),
+                default: Some(default.to_string()),
+            },
+        };
+        let generics = |params| Generics {
+            params,
+            where_predicates: Vec::new(),
+        };
+
+        let mut output = String::new();
+        format_generics(
+            &mut output,
+            &generics(vec![type_param("T", None), const_param("N", "32")]),
+            &data,
+        );
+        assert_eq!(output, "<T, const N: usize = 32>");
+
+        let mut output = String::new();
+        format_generics(
+            &mut output,
+            &generics(vec![type_param("T", Some(primitive("i32")))]),
+            &data,
+        );
+        assert_eq!(output, "<T = i32>");
+
+        let mut output = String::new();
+        format_generics(
+            &mut output,
+            &generics(vec![
+                const_param("N", "4 * 8"),
+                const_param("M", "{ N + 1 }"),
+            ]),
+            &data,
+        );
+        assert_eq!(
+            output,
+            "<const N: usize = { 4 * 8 }, const M: usize = { N + 1 }>"
+        );
+    }
+
+    #[test]
+    fn doc_asset_urls_are_rebased_without_touching_intra_doc_links() {
+        let options = MarkdownOptions::new().doc_asset_base_url("https://example.com/repo/");
+        let rebased = |docs| render_docs(docs, 2, &options);
+
+        assert_eq!(
+            rebased("![Logo](./docs/logo.png) and [guide](guide.md#setup)"),
+            "![Logo](https://example.com/repo/docs/logo.png) and [guide](https://example.com/repo/guide.md#setup)"
+        );
+        assert_eq!(
+            rebased("See [`Parser`](crate::Parser), [`Vec`](Vec) and [top](#top)."),
+            "See [`Parser`](crate::Parser), [`Vec`](Vec) and [top](#top)."
+        );
+        assert_eq!(
+            rebased("# Using [the CLI](docs/cli.md)"),
+            "### Using [the CLI](https://example.com/repo/docs/cli.md)"
+        );
+        assert_eq!(
+            rebased("[logo]: docs/logo.png \"Logo\"\n[`Parser`]: crate::Parser\n[^1]: notes/a.md"),
+            "[logo]: https://example.com/repo/docs/logo.png \"Logo\"\n[`Parser`]: crate::Parser\n[^1]: notes/a.md"
+        );
+        assert_eq!(
+            rebased("```text\n![Logo](docs/logo.png)\n```"),
+            "```text\n![Logo](docs/logo.png)\n```"
+        );
+    }
+
+    #[test]
+    fn mutable_self_receivers_render_as_mut_self() {
+        let self_ref = |is_mutable| Type::BorrowedRef {
+            lifetime: None,
+            is_mutable,
+            type_: Box::new(Type::Generic("Self".to_string())),
+        };
+        let data = test_crate(
+            &[1],
+            vec![
+                item(1, "Counter", struct_with_fields(&[], &[10])),
+                impl_item(10, None, resolved("Counter", 1, None), &[11, 12]),
+                item(
+                    11,
+                    "get",
+                    function(vec![("self", self_ref(false))], Some(primitive("u32"))),
+                ),
+                item(
+                    12,
+                    "bump",
+                    function(
+                        vec![("self", self_ref(true)), ("by", primitive("u32"))],
+                        None,
+                    ),
+                ),
+            ],
+        );
+
+        let output = render(&data);
+        assert!(
+            output.contains("pub fn bump(&mut self, by: u32) { /* ... */ }"),
+            "{}",
+            output
+        );
+        assert!(
+            output.contains("pub fn get(&self) -> u32 { /* ... */ }"),
+            "{}",
+            output
+        );
+    }
+
+    #[test]
+    fn sort_methods_lists_methods_by_name_in_any_input_order() {
+        let counter = |method_ids: &[u32]| {
+            let mut items = vec![
+                item(1, "Counter", struct_with_fields(&[], &[10])),
+                impl_item(10, None, resolved("Counter", 1, None), method_ids),
+            ];
+            for (id, name) in [(11, "reset"), (12, "add"), (13, "get"), (14, "new")] {
+                items.push(item(id, name, function(Vec::new(), None)));
+            }
+            test_crate(&[1], items)
+        };
+        let method_order = |output: &str| -> Vec<String> {
+            output
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("pub fn "))
+                .map(|rest| rest.split('(').next().unwrap_or("").to_string())
+                .collect()
+        };
+        let sorted = MarkdownOptions::new().sort_methods(true);
+
+        for order in [[13, 11, 14, 12], [12, 14, 11, 13], [14, 13, 12, 11]] {
+            let data = counter(&order);
+            assert_eq!(
+                method_order(&sorted.render(&data)),
+                ["add", "get", "new", "reset"]
+            );
+        }
+        assert_eq!(
+            method_order(&render(&counter(&[13, 11, 14, 12]))),
+            ["get", "reset", "new", "add"]
+        );
+    }
+
+    #[test]
+    fn renamed_extern_crates_render_the_original_name_first() {
+        // As rustdoc encodes `pub extern crate core as mycore;`
+        let mycore = item(
+            1,
+            "mycore",
+            ItemEnum::ExternCrate {
+                name: "mycore".to_string(),
+                rename: Some("core".to_string()),
+            },
+        );
+        let alloc = item(
+            2,
+            "alloc",
+            ItemEnum::ExternCrate {
+                name: "alloc".to_string(),
+                rename: None,
+            },
+        );
+        let data = test_crate(&[1, 2], vec![mycore.clone(), alloc.clone()]);
+
+        assert_eq!(
+            signature(&mycore, &data),
+            "pub extern crate core as mycore;"
+        );
+        assert_eq!(signature(&alloc, &data), "pub extern crate alloc;");
+        let output = render(&data);
+        assert!(
+            output.contains("### Extern Crate `core` as `mycore`\n"),
+            "{}",
+            output
+        );
+        assert!(output.contains("### Extern Crate `alloc`\n"), "{}", output);
+    }
+
+    #[test]
+    fn only_module_level_foreign_items_render_inside_an_extern_block() {
+        let extern_c = |id, name| {
+            let ItemEnum::Function(function) = function(vec![("code", primitive("i32"))], None)
+            else {
+                unreachable!()
+            };
+            assoc_item(
+                id,
+                name,
+                ItemEnum::Function(Function {
+                    header: FunctionHeader {
+                        abi: Abi::C { unwind: false },
+                        ..function.header
+                    },
+                    has_body: false,
+                    ..function
+                }),
+            )
+        };
+        // `pub trait Callback { extern "C" fn on_event(code: i32); }`
+        let on_event = extern_c(10, "on_event");
+        let free = extern_c(11, "free");
+        let opaque = item(12, "Opaque", ItemEnum::ExternType);
+        let data = test_crate(
+            &[1, 11, 12],
+            vec![
+                item(1, "Callback", trait_with_items(&[10], &[])),
+                on_event.clone(),
+                free.clone(),
+                opaque.clone(),
+            ],
+        );
+
+        assert_eq!(
+            signature(&on_event, &data),
+            "extern \"C\" fn on_event(code: i32);"
+        );
+        assert_eq!(
+            signature(&free, &data),
+            "extern \"C\" {\n    fn free(code: i32);\n}"
+        );
+        assert_eq!(
+            signature(&opaque, &data),
+            "extern {\n    pub type Opaque;\n}"
+        );
+    }
+
+    #[test]
+    fn first_line_only_table_docs_keep_just_the_first_line() {
+        let docs = "\n  Line one of the doc\ncontinues here.\n\nSecond paragraph.";
+        let options = MarkdownOptions::new();
+        assert_eq!(options.table_doc_mode, TableDocMode::FirstLineOnly);
+        assert_eq!(escape_table_cell(docs, &options), "Line one of the doc");
+        assert_eq!(
+            escape_table_cell(docs, &options.clone().table_doc_mode(TableDocMode::Escaped)),
+            "Line one of the doc continues here. Second paragraph."
+        );
+    }
+
+    #[test]
+    fn crate_name_title_is_the_only_top_level_heading() {
+        let data = test_crate(&[1], vec![item(1, "Config", struct_with_fields(&[], &[]))]);
+        let top_level = |output: &str| -> Vec<String> {
+            output
+                .lines()
+                .filter(|line| line.starts_with("# "))
+                .map(str::to_string)
+                .collect()
+        };
+
+        assert_eq!(top_level(&render(&data)), ["# demo"]);
+        assert_eq!(
+            top_level(&MarkdownOptions::new().title("API").render(&data)),
+            ["# API", "# Module `demo`"]
+        );
     }
 }
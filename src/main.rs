@@ -1,11 +1,24 @@
-use std::{fs, io, path::PathBuf};
+#[cfg(feature = "compression")]
+use std::io::BufReader;
+#[cfg(feature = "network")]
+use std::io::Read;
+use std::{fs, path::PathBuf, process::Command};
 
-use clap::{ArgGroup, Parser};
+use clap::{ArgGroup, Parser, ValueEnum};
 use eyre::bail;
-use rustdoc_md::rustdoc_json_to_markdown;
+use rustdoc_md::{
+    DiagramBlocks, DocModel, DocStats, HeadingStyle, MarkdownOptions, TableDocMode,
+    closest_module_paths, diff_crates, markdown_to_plain_text, placeholders::OUTPUT_TRUNCATED_NOTE,
+    render_module_subtree, render_public_api, unresolved_paths,
+};
 use rustdoc_types::Crate;
+use serde::Deserialize;
 
+#[cfg(feature = "compression")]
+use flate2::read::GzDecoder;
+#[cfg(feature = "network")]
 use ureq::http::StatusCode;
+#[cfg(feature = "compression")]
 use zstd::decode_all;
 
 #[derive(Parser)]
@@ -13,13 +26,18 @@ use zstd::decode_all;
 #[command(group(
     ArgGroup::new("input")
         .required(true)
-        .args(&["path", "crate_name"]),
+        .args(&["path", "crate_name", "from_cargo"]),
 ))]
 struct Cli {
     /// The path to a local rust docs json file.
     #[arg(short, long)]
     path: Option<PathBuf>,
 
+    /// Generate the rustdoc JSON for the crate in the current directory with
+    /// `cargo +nightly rustdoc`, then render it.
+    #[arg(long)]
+    from_cargo: bool,
+
     /// The name of the crate to fetch from docs.rs.
     #[arg(long)]
     crate_name: Option<String>,
@@ -28,7 +46,14 @@ struct Cli {
     #[arg(long, default_value = "latest", requires = "crate_name")]
     crate_version: String,
 
+    /// The base URL of the docs.rs instance to fetch from. Requires --crate-name.
+    #[arg(long, default_value = "https://docs.rs", requires = "crate_name")]
+    docs_base_url: String,
+
     /// The target triple to fetch documentation for. Requires --crate-name.
+    ///
+    /// Accepts a comma-separated list of targets, which are tried in order until
+    /// one has rustdoc JSON available.
     #[arg(
         long,
         default_value = "x86_64-unknown-linux-gnu",
@@ -39,50 +64,471 @@ struct Cli {
     /// The path to the output markdown file.
     #[arg(short, long)]
     output: PathBuf,
+
+    /// Render function parameters as types only, omitting their names.
+    #[arg(long)]
+    elide_param_names: bool,
+
+    /// Skip items that have no doc comment.
+    #[arg(long)]
+    only_documented: bool,
+
+    /// Leave out the signature code block under each item, for a compact overview.
+    #[arg(long)]
+    no_signatures: bool,
+
+    /// Render re-exported items in full where they're re-exported, e.g. for a
+    /// curated prelude.
+    #[arg(long)]
+    flatten_reexports: bool,
+
+    /// Remove hidden doctest lines (starting with `# `) from Rust code blocks in docs.
+    #[arg(long)]
+    strip_doc_tests: bool,
+
+    /// Leave out items whose path matches this glob, e.g. `my_crate::internal::*`.
+    /// Can be given multiple times.
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+
+    /// Stop adding top-level items once the Markdown output reaches this many bytes.
+    #[arg(long, value_name = "BYTES")]
+    max_output_bytes: Option<usize>,
+
+    /// How to fit multi-line docs into field table cells.
+    #[arg(long, value_enum, default_value_t = TableDocs::FirstLine)]
+    table_docs: TableDocs,
+
+    /// How to introduce each item.
+    #[arg(long, value_enum, default_value_t = HeadingStyleArg::Headings)]
+    heading_style: HeadingStyleArg,
+
+    /// Add a line counting the crate's items by kind to the crate header.
+    #[arg(long)]
+    header_stats: bool,
+
+    /// How many levels of modules to document, counting the crate root as 0. Deeper
+    /// submodules are listed in a note instead of being rendered.
+    #[arg(long)]
+    max_depth: Option<usize>,
+
+    /// List the methods of each impl alphabetically rather than in source order.
+    #[arg(long)]
+    sort_methods: bool,
+
+    /// Keep nesting submodules and their contents below their parent's heading (up
+    /// to level 6) instead of restarting each module at level 2.
+    #[arg(long)]
+    preserve_module_depth: bool,
+
+    /// Leave the rustdoc JSON format version out of the crate header.
+    #[arg(long)]
+    no_format_version: bool,
+
+    /// Prefix tuple struct fields in signatures with their index, e.g. `/* 0 */ pub f64`.
+    #[arg(long)]
+    tuple_field_indices: bool,
+
+    /// The most implementors to list for a trait before summarizing the rest as a
+    /// count. Pass 0 to list them all.
+    #[arg(long, default_value_t = 50)]
+    max_implementors: usize,
+
+    /// How many spaces to indent each level of a nested list. Some Markdown
+    /// renderers need 4 for code blocks to stay inside their list items.
+    #[arg(long, default_value_t = 2)]
+    list_indent: usize,
+
+    /// A base URL to resolve relative image and link URLs in docs against, e.g.
+    /// `https://raw.githubusercontent.com/owner/repo/main`.
+    #[arg(long)]
+    doc_asset_base_url: Option<String>,
+
+    /// Markdown to append after all items. `{date}` is replaced with the current
+    /// UTC date and `{version}` with the crate's version.
+    #[arg(long)]
+    footer: Option<String>,
+
+    /// The top-level heading of the document (defaults to the crate's name).
+    #[arg(long)]
+    title: Option<String>,
+
+    /// The crate's Rust edition, shown in the crate header.
+    #[arg(long)]
+    edition: Option<String>,
+
+    /// The crate's minimum supported Rust version, shown in the crate header.
+    #[arg(long)]
+    msrv: Option<String>,
+
+    /// Only document the module at this path (e.g. `my_crate::net::http`) and its
+    /// descendants. Only applies to Markdown and text output.
+    #[arg(long)]
+    module_path: Option<String>,
+
+    /// Write only the signature of every public item, method and trait item, grouped
+    /// by module, for diffing the API surface between versions. Only applies to
+    /// Markdown and text output.
+    #[arg(long, conflicts_with = "module_path")]
+    only_public_api: bool,
+
+    /// Compare against this older rustdoc JSON file and write a report of the
+    /// public API changes instead. Only applies to Markdown and text output.
+    #[arg(long, value_name = "OLD_JSON", conflicts_with_all = ["module_path", "only_public_api"])]
+    diff: Option<PathBuf>,
+
+    /// Print a summary of item counts and documentation coverage to stderr.
+    #[arg(long)]
+    stats: bool,
+
+    /// Print the output path and the size of what would be written, without writing it.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Don't print anything other than errors.
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// The format to write the output in.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Markdown)]
+    format: OutputFormat,
+
+    /// Label fenced diagram blocks (e.g. mermaid) in docs, for viewers that can't draw them.
+    #[arg(long)]
+    label_diagrams: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Rendered Markdown documentation.
+    Markdown,
+    /// The simplified item tree, as JSON.
+    Json,
+    /// The Markdown documentation with its formatting stripped, as plain text.
+    Text,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum HeadingStyleArg {
+    /// A heading per item.
+    Headings,
+    /// A bold term per item, followed by its docs and signature.
+    DefinitionList,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TableDocs {
+    /// Only the first line.
+    FirstLine,
+    /// Every line, separated by HTML `<br>` tags.
+    HtmlBreaks,
+    /// Every line, joined with spaces.
+    Escaped,
 }
 
 fn main() -> eyre::Result<()> {
     let cli = Cli::parse();
 
+    let mut fetched_target = None;
     let data: Crate = if let Some(path) = cli.path {
-        let file = fs::File::open(path)?;
-        let reader = io::BufReader::new(file);
-        serde_json::from_reader(reader)?
+        decode_crate_json(&fs::read(path)?)?
     } else if let Some(crate_name) = cli.crate_name {
-        let url = format!(
-            "https://docs.rs/crate/{crate_name}/{}/{}/json",
-            cli.crate_version, cli.target
-        );
+        let targets = cli
+            .target
+            .split(',')
+            .map(str::trim)
+            .filter(|t| !t.is_empty());
 
-        let resp = ureq::get(&url)
-            .header(
-                "user-agent",
-                concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
-            )
-            .call()?;
-        let status = resp.status();
-        if !status.is_success() {
-            match status {
-                StatusCode::NOT_FOUND => {
-                    bail!("crate or version not found, or doesn't provide rustdocs as json");
-                }
-                _ => {
-                    bail!("failed to fetch crate json: {status}");
-                }
+        let mut data = None;
+        for target in targets {
+            let url = format!(
+                "{}/crate/{crate_name}/{}/{target}/json",
+                cli.docs_base_url.trim_end_matches('/'),
+                cli.crate_version,
+            );
+
+            // Move on to the next target if this one has no JSON
+            if let Some(target_data) = fetch_crate_json(&url)? {
+                data = Some(target_data);
+                fetched_target = Some(target.to_string());
+                break;
             }
         }
 
-        let reader = resp.into_body().into_reader();
-        let body = decode_all(reader)?;
-        serde_json::from_reader(body.as_slice())?
+        match data {
+            Some(data) => data,
+            None => bail!(
+                "crate or version not found, or doesn't provide rustdocs as json for target(s) {}",
+                cli.target
+            ),
+        }
+    } else if cli.from_cargo {
+        decode_crate_json(&fs::read(generate_crate_json()?)?)?
     } else {
-        unreachable!("neither --path nor --crate-name set");
+        unreachable!("neither --path, --crate-name nor --from-cargo set");
     };
 
-    let md = rustdoc_json_to_markdown(data);
-    fs::write(&cli.output, md)?;
+    let mut options = MarkdownOptions::new()
+        .diagram_blocks(if cli.label_diagrams {
+            DiagramBlocks::Label
+        } else {
+            DiagramBlocks::Preserve
+        })
+        .elide_param_names(cli.elide_param_names)
+        .only_documented(cli.only_documented)
+        .tuple_field_indices(cli.tuple_field_indices)
+        .include_signatures(!cli.no_signatures)
+        .flatten_reexports(cli.flatten_reexports)
+        .max_output_bytes(cli.max_output_bytes)
+        .include_stats(cli.header_stats)
+        .show_format_version(!cli.no_format_version)
+        .preserve_module_depth(cli.preserve_module_depth)
+        .max_depth(cli.max_depth)
+        .sort_methods(cli.sort_methods)
+        .list_indent(cli.list_indent)
+        .table_doc_mode(match cli.table_docs {
+            TableDocs::FirstLine => TableDocMode::FirstLineOnly,
+            TableDocs::HtmlBreaks => TableDocMode::HtmlBreaks,
+            TableDocs::Escaped => TableDocMode::Escaped,
+        })
+        .heading_style(match cli.heading_style {
+            HeadingStyleArg::Headings => HeadingStyle::Headings,
+            HeadingStyleArg::DefinitionList => HeadingStyle::DefinitionList,
+        })
+        .strip_doc_tests(cli.strip_doc_tests)
+        .max_implementors((cli.max_implementors > 0).then_some(cli.max_implementors));
+    for pattern in cli.exclude {
+        options = options.exclude(pattern);
+    }
+    if let Some(title) = cli.title {
+        options = options.title(title);
+    }
+    if let Some(footer) = cli.footer {
+        options = options.footer(footer);
+    }
+    if let Some(doc_asset_base_url) = cli.doc_asset_base_url {
+        options = options.doc_asset_base_url(doc_asset_base_url);
+    }
+    if let Some(edition) = cli.edition {
+        options = options.edition(edition);
+    }
+    if let Some(msrv) = cli.msrv {
+        options = options.msrv(msrv);
+    }
 
-    println!("successfully wrote to file {}", cli.output.display());
+    if !cli.quiet {
+        let unresolved = unresolved_paths(&data);
+        if !unresolved.is_empty() {
+            let examples: Vec<&str> = unresolved.iter().take(3).map(String::as_str).collect();
+            eprintln!(
+                "note: {} referenced paths (e.g. {}) have no path information in the JSON, so they can't be resolved or linked; this is usual for items from dependencies that weren't documented with the crate",
+                unresolved.len(),
+                examples.join(", ")
+            );
+        }
+    }
+
+    let stats = match &cli.module_path {
+        _ if !cli.stats || cli.quiet => None,
+        Some(module_path) => DocStats::collect_module_subtree(&data, module_path, &options),
+        None => Some(DocStats::collect(&data, &options)),
+    };
+
+    let old_data = match &cli.diff {
+        Some(old_path) => Some(decode_crate_json(&fs::read(old_path)?)?),
+        None => None,
+    };
+
+    let contents = match cli.format {
+        OutputFormat::Markdown | OutputFormat::Text if cli.only_public_api => {
+            render_public_api(&data, &options)
+        }
+        OutputFormat::Markdown | OutputFormat::Text => match (&old_data, &cli.module_path) {
+            (Some(old), _) => diff_crates(old, &data, &options),
+            (None, Some(module_path)) => {
+                match render_module_subtree(&data, module_path, &options) {
+                    Some(md) => md,
+                    None => {
+                        let closest = closest_module_paths(&data, module_path, 5);
+                        bail!(
+                            "module `{module_path}` not found, closest matches: {}",
+                            closest.join(", ")
+                        );
+                    }
+                }
+            }
+            (None, None) => options.render(&data),
+        },
+        OutputFormat::Json => serde_json::to_string_pretty(&DocModel::from_crate(&data, &options))?,
+    };
+    // The budget only stops further items, so the output can go over it with
+    // nothing left out; the note is only there when something was
+    if let Some(max_output_bytes) = cli.max_output_bytes
+        && matches!(cli.format, OutputFormat::Markdown | OutputFormat::Text)
+        && contents.contains(OUTPUT_TRUNCATED_NOTE)
+    {
+        eprintln!(
+            "warning: output reached the limit of {max_output_bytes} bytes; items past the limit were left out"
+        );
+    }
+    let contents = match cli.format {
+        OutputFormat::Text => markdown_to_plain_text(&contents),
+        OutputFormat::Markdown | OutputFormat::Json => contents,
+    };
+
+    // Leave an identical existing file alone, so its mtime and git status stay clean
+    let unchanged = fs::read(&cli.output).is_ok_and(|existing| existing == contents.as_bytes());
+    if cli.dry_run {
+        println!("{} ({} bytes)", cli.output.display(), contents.len());
+    } else if !unchanged {
+        fs::write(&cli.output, contents)?;
+    }
+
+    if !cli.quiet && !cli.dry_run {
+        match fetched_target {
+            _ if unchanged => println!("file {} is already up to date", cli.output.display()),
+            Some(target) => println!(
+                "successfully wrote to file {} (target {target})",
+                cli.output.display()
+            ),
+            None => println!("successfully wrote to file {}", cli.output.display()),
+        }
+    }
+
+    if let Some(stats) = stats {
+        eprintln!("{stats}");
+    }
 
     Ok(())
 }
+
+/// Fetches and decodes rustdoc JSON from docs.rs, returning `None` if there's no
+/// JSON at `url`.
+#[cfg(feature = "network")]
+fn fetch_crate_json(url: &str) -> eyre::Result<Option<Crate>> {
+    let resp = match ureq::get(url)
+        .header(
+            "user-agent",
+            concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
+        )
+        .call()
+    {
+        Ok(resp) => resp,
+        Err(ureq::Error::StatusCode(404)) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let status = resp.status();
+    if !status.is_success() {
+        match status {
+            StatusCode::NOT_FOUND => return Ok(None),
+            _ => {
+                bail!("failed to fetch crate json: {status}");
+            }
+        }
+    }
+
+    let mut body = Vec::new();
+    resp.into_body().into_reader().read_to_end(&mut body)?;
+    Ok(Some(decode_crate_json(&body)?))
+}
+
+#[cfg(not(feature = "network"))]
+fn fetch_crate_json(_url: &str) -> eyre::Result<Option<Crate>> {
+    bail!("fetching from docs.rs requires rustdoc-md to be built with the `network` feature");
+}
+
+/// The parts of `cargo metadata` output needed to find a package's rustdoc JSON.
+#[derive(Deserialize)]
+struct CargoMetadata {
+    packages: Vec<CargoPackage>,
+    target_directory: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct CargoPackage {
+    manifest_path: PathBuf,
+    targets: Vec<CargoTarget>,
+}
+
+#[derive(Deserialize)]
+struct CargoTarget {
+    name: String,
+    kind: Vec<String>,
+}
+
+/// Runs `cargo +nightly rustdoc` on the package in the current directory to
+/// generate its rustdoc JSON, returning the path of the JSON file.
+fn generate_crate_json() -> eyre::Result<PathBuf> {
+    let output = Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()?;
+    if !output.status.success() {
+        bail!(
+            "cargo metadata failed, is there a Cargo.toml in the current directory?\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    let metadata: CargoMetadata = serde_json::from_slice(&output.stdout)?;
+
+    // Pick the package whose manifest is in the current directory, as a workspace
+    // root may list several
+    let manifest_path = std::env::current_dir()?.join("Cargo.toml");
+    let package = match metadata
+        .packages
+        .iter()
+        .find(|package| package.manifest_path == manifest_path)
+    {
+        Some(package) => package,
+        None if metadata.packages.len() == 1 => &metadata.packages[0],
+        None => bail!("no single package found in the current directory; pass --path instead"),
+    };
+    let Some(lib) = package
+        .targets
+        .iter()
+        .find(|target| target.kind.iter().any(|kind| kind == "lib"))
+    else {
+        bail!("the package in the current directory has no library target to document");
+    };
+
+    let status = Command::new("cargo")
+        .args([
+            "+nightly",
+            "rustdoc",
+            "--lib",
+            "--",
+            "-Z",
+            "unstable-options",
+            "--output-format",
+            "json",
+        ])
+        .status()?;
+    if !status.success() {
+        bail!("cargo +nightly rustdoc failed; is a nightly toolchain installed?");
+    }
+
+    Ok(metadata
+        .target_directory
+        .join("doc")
+        .join(format!("{}.json", lib.name.replace('-', "_"))))
+}
+
+/// Decodes rustdoc JSON that may be gzip or zstd compressed, picking the decoder
+/// from the input's magic bytes and falling back to plain JSON.
+fn decode_crate_json(bytes: &[u8]) -> eyre::Result<Crate> {
+    const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+    const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+    let data = if bytes.starts_with(GZIP_MAGIC) || bytes.starts_with(ZSTD_MAGIC) {
+        #[cfg(feature = "compression")]
+        if bytes.starts_with(GZIP_MAGIC) {
+            serde_json::from_reader(BufReader::new(GzDecoder::new(bytes)))?
+        } else {
+            serde_json::from_slice(&decode_all(bytes)?)?
+        }
+        #[cfg(not(feature = "compression"))]
+        bail!("decoding compressed JSON requires the `compression` feature");
+    } else {
+        serde_json::from_slice(bytes)?
+    };
+    Ok(data)
+}
@@ -0,0 +1,169 @@
+use rustdoc_types::{Crate, Id, Item, ItemEnum, StructKind, VariantKind};
+use serde::Serialize;
+
+use crate::{MarkdownOptions, format_item_signature, group_items, item_kind_name};
+
+/// A simplified, serializable tree of the items documented in a crate.
+///
+/// This is the same structure the Markdown output is rendered from, for users who
+/// want to build their own renderer on top of it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocModel {
+    pub crate_version: Option<String>,
+    pub format_version: u32,
+    /// The crate's root module, if it's present in the index.
+    pub root: Option<DocItem>,
+}
+
+/// A single documented item and the items nested under it.
+#[derive(Debug, Clone, Serialize)]
+pub struct DocItem {
+    pub name: Option<String>,
+    /// The kind of item, e.g. `"struct"` or `"function"`.
+    pub kind: &'static str,
+    /// The item's signature, formatted as Rust source.
+    pub signature: String,
+    pub docs: Option<String>,
+    pub children: Vec<DocItem>,
+}
+
+impl DocModel {
+    /// Builds the item tree for a crate, starting at its root module. Signatures
+    /// are formatted according to `options`.
+    pub fn from_crate(data: &Crate, options: &MarkdownOptions) -> Self {
+        DocModel {
+            crate_version: data.crate_version.clone(),
+            format_version: data.format_version,
+            root: data
+                .index
+                .get(&data.root)
+                .map(|root_item| DocItem::from_item(root_item, data, options)),
+        }
+    }
+}
+
+impl DocItem {
+    fn from_item(item: &Item, data: &Crate, options: &MarkdownOptions) -> Self {
+        let mut signature = String::new();
+        format_item_signature(&mut signature, item, data, options);
+
+        DocItem {
+            name: item.name.clone(),
+            kind: item_kind_name(item),
+            signature,
+            docs: item.docs.clone(),
+            children: child_ids(item, data)
+                .iter()
+                .filter_map(|id| data.index.get(id))
+                .map(|child| DocItem::from_item(child, data, options))
+                .collect(),
+        }
+    }
+}
+
+/// Returns the ids of the items nested directly under an item, in render order.
+fn child_ids(item: &Item, data: &Crate) -> Vec<Id> {
+    match &item.inner {
+        ItemEnum::Module(module) => group_items(&module.items, data)
+            .into_iter()
+            .flat_map(|(_, ids)| ids)
+            .collect(),
+        ItemEnum::Struct(struct_) => {
+            let mut ids = match &struct_.kind {
+                StructKind::Unit => Vec::new(),
+                StructKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+                StructKind::Plain { fields, .. } => fields.clone(),
+            };
+            ids.extend(sorted_impls(&struct_.impls, data));
+            ids
+        }
+        ItemEnum::Enum(enum_) => {
+            let mut ids = enum_.variants.clone();
+            ids.extend(sorted_impls(&enum_.impls, data));
+            ids
+        }
+        ItemEnum::Variant(variant) => match &variant.kind {
+            VariantKind::Plain => Vec::new(),
+            VariantKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+            VariantKind::Struct { fields, .. } => fields.clone(),
+        },
+        ItemEnum::Union(union_) => {
+            let mut ids = union_.fields.clone();
+            ids.extend(sorted_impls(&union_.impls, data));
+            ids
+        }
+        ItemEnum::Trait(trait_) => trait_.items.clone(),
+        ItemEnum::Impl(impl_) => impl_.items.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// Orders impls with inherent impls first, then trait impls sorted by trait path,
+/// so the model is stable regardless of the order in the JSON.
+fn sorted_impls(impl_ids: &[Id], data: &Crate) -> Vec<Id> {
+    let mut impls: Vec<(Option<&str>, Id)> = impl_ids
+        .iter()
+        .filter_map(|impl_id| match &data.index.get(impl_id)?.inner {
+            ItemEnum::Impl(impl_) => Some((
+                impl_.trait_.as_ref().map(|trait_| trait_.path.as_str()),
+                *impl_id,
+            )),
+            _ => None,
+        })
+        .collect();
+    impls.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.0.cmp(&b.1.0)));
+    impls.into_iter().map(|(_, id)| id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MarkdownOptions;
+    use crate::tests::{
+        documented, impl_item, item, item_path, primitive, resolved, struct_with_fields, test_crate,
+    };
+    use rustdoc_types::ItemEnum;
+
+    use super::DocModel;
+
+    #[test]
+    fn impls_are_ordered_inherent_first_then_by_trait() {
+        let point = || resolved("Point", 1, None);
+        let data = test_crate(
+            &[1],
+            vec![
+                documented(
+                    item(1, "Point", struct_with_fields(&[2], &[12, 11, 10])),
+                    "A point.",
+                ),
+                item(2, "x", ItemEnum::StructField(primitive("f64"))),
+                impl_item(10, Some(item_path("Debug", 101, None)), point(), &[]),
+                impl_item(11, Some(item_path("Clone", 100, None)), point(), &[]),
+                impl_item(12, None, point(), &[]),
+            ],
+        );
+
+        let model = DocModel::from_crate(&data, &MarkdownOptions::default());
+        let root = model.root.expect("root module is in the index");
+        assert_eq!(root.kind, "module");
+        let [point] = root.children.as_slice() else {
+            panic!("expected only `Point`: {:?}", root.children);
+        };
+        assert_eq!(point.name.as_deref(), Some("Point"));
+        assert_eq!(point.kind, "struct");
+        assert_eq!(point.docs.as_deref(), Some("A point."));
+        let children: Vec<&str> = point
+            .children
+            .iter()
+            .map(|child| child.signature.lines().next().unwrap_or(""))
+            .collect();
+        assert_eq!(
+            children,
+            [
+                "pub x: f64",
+                "impl Point {",
+                "impl Clone for Point {",
+                "impl Debug for Point {",
+            ]
+        );
+    }
+}
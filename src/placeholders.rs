@@ -0,0 +1,40 @@
+//! Placeholder text shown where rustdoc has left out private or hidden items.
+//!
+//! These are kept in one place so forks can match their own house style or
+//! language by changing them here.
+
+/// Stands in for a private tuple field in a signature, e.g. `struct T(pub u8, /* private field */);`.
+pub const PRIVATE_FIELD: &str = "/* private field */";
+
+/// Ends the fields of a struct, union or struct variant signature when some
+/// are private or hidden.
+pub const FIELDS_OMITTED: &str = "// Some fields omitted";
+
+/// Ends the variants of an enum signature when some are hidden.
+pub const VARIANTS_OMITTED: &str = "// Some variants omitted";
+
+/// The type column of a private tuple field's row in a fields table.
+pub const PRIVATE_FIELD_TYPE_CELL: &str = "`private`";
+
+/// The documentation column of a private tuple field's row in a fields table.
+pub const PRIVATE_FIELD_CELL: &str = "*Private field*";
+
+/// The name column of the row that ends a fields table when some fields are
+/// private or hidden.
+pub const PRIVATE_FIELDS_CELL: &str = "*private fields*";
+
+/// The documentation column of the row that ends a fields table when some
+/// fields are private or hidden.
+pub const FIELDS_OMITTED_CELL: &str = "*Some fields have been omitted*";
+
+/// Shown under an enum's variants when some are private or hidden.
+pub const VARIANTS_OMITTED_NOTE: &str =
+    "*Note: Some variants have been omitted because they are private or hidden.*";
+
+/// Shown at the top of a module that rustdoc marked as stripped.
+pub const STRIPPED_MODULE_NOTE: &str =
+    "> **Note:** This module is marked as stripped. Some items may be omitted.";
+
+/// Ends the output when `max_output_bytes` left out the remaining top-level items.
+pub const OUTPUT_TRUNCATED_NOTE: &str =
+    "> **Note:** Output reached its size limit; the remaining items were left out.";
@@ -0,0 +1,195 @@
+use std::fmt;
+
+use rustdoc_types::{Crate, Item, ItemEnum};
+
+use crate::{MarkdownOptions, find_module, has_docs, partition_by_depth, rendered_items};
+
+/// Counts of the items documented in a crate, for a quick sense of its size and
+/// how much of it has doc comments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocStats {
+    pub modules: usize,
+    pub structs: usize,
+    pub enums: usize,
+    pub traits: usize,
+    pub functions: usize,
+    pub total_items: usize,
+    pub documented: usize,
+    pub undocumented: usize,
+}
+
+impl DocStats {
+    /// Counts the items [`MarkdownOptions::render`] documents: those reachable
+    /// from the crate's root module that `options` doesn't leave out.
+    pub fn collect(data: &Crate, options: &MarkdownOptions) -> Self {
+        match data.index.get(&data.root) {
+            Some(root_item) => Self::collect_from(root_item, data, options),
+            None => DocStats::default(),
+        }
+    }
+
+    /// Counts the items [`render_module_subtree`](crate::render_module_subtree)
+    /// documents for the module at `path`, or `None` if there's no such module.
+    pub fn collect_module_subtree(
+        data: &Crate,
+        path: &str,
+        options: &MarkdownOptions,
+    ) -> Option<Self> {
+        Some(Self::collect_from(find_module(data, path)?, data, options))
+    }
+
+    pub(crate) fn collect_from(root_item: &Item, data: &Crate, options: &MarkdownOptions) -> Self {
+        let mut stats = DocStats::default();
+        stats.visit(root_item, data, options);
+        stats
+    }
+
+    /// A one-line summary of the item counts, e.g. `3 structs, 1 enum, 5 functions`.
+    /// Kinds with no items are left out.
+    pub fn summary(&self) -> String {
+        [
+            (self.modules, "module", "modules"),
+            (self.structs, "struct", "structs"),
+            (self.enums, "enum", "enums"),
+            (self.traits, "trait", "traits"),
+            (self.functions, "function", "functions"),
+        ]
+        .into_iter()
+        .filter(|(count, _, _)| *count > 0)
+        .map(|(count, singular, plural)| {
+            format!("{} {}", count, if count == 1 { singular } else { plural })
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
+    fn visit(&mut self, item: &Item, data: &Crate, options: &MarkdownOptions) {
+        match &item.inner {
+            ItemEnum::Module(_) => self.modules += 1,
+            ItemEnum::Struct(_) => self.structs += 1,
+            ItemEnum::Enum(_) => self.enums += 1,
+            ItemEnum::Trait(_) => self.traits += 1,
+            ItemEnum::Function(_) => self.functions += 1,
+            _ => {}
+        }
+
+        self.total_items += 1;
+        if has_docs(item) {
+            self.documented += 1;
+        } else {
+            self.undocumented += 1;
+        }
+
+        // Walk the same items the renderer does, so the counts match the output
+        if let ItemEnum::Module(module) = &item.inner {
+            let (items, _) = partition_by_depth(&item.id, module, data, options);
+            for child_id in rendered_items(&items, data, options) {
+                if let Some(child) = data.index.get(&child_id) {
+                    self.visit(child, data, options);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for DocStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "modules:     {}", self.modules)?;
+        writeln!(f, "structs:     {}", self.structs)?;
+        writeln!(f, "enums:       {}", self.enums)?;
+        writeln!(f, "traits:      {}", self.traits)?;
+        writeln!(f, "functions:   {}", self.functions)?;
+        write!(
+            f,
+            "total items: {} ({} documented, {} undocumented)",
+            self.total_items, self.documented, self.undocumented
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_types::{Id, ItemEnum, ItemKind, ItemSummary, Module};
+
+    use crate::MarkdownOptions;
+    use crate::tests::{documented, function, item, struct_with_fields, test_crate};
+
+    use super::DocStats;
+
+    #[test]
+    fn counts_reachable_items_and_their_docs() {
+        let mut data = test_crate(
+            &[1, 2],
+            vec![
+                documented(
+                    item(
+                        1,
+                        "net",
+                        ItemEnum::Module(Module {
+                            is_crate: false,
+                            items: vec![Id(3), Id(4)],
+                            is_stripped: false,
+                        }),
+                    ),
+                    "Networking.",
+                ),
+                item(2, "Config", struct_with_fields(&[], &[])),
+                documented(item(3, "connect", function(Vec::new(), None)), "Connects."),
+                item(4, "listen", function(Vec::new(), None)),
+                // Not reachable from the root module
+                item(5, "orphan", function(Vec::new(), None)),
+            ],
+        );
+
+        for (id, path, kind) in [
+            (0, vec!["demo"], ItemKind::Module),
+            (1, vec!["demo", "net"], ItemKind::Module),
+            (2, vec!["demo", "Config"], ItemKind::Struct),
+        ] {
+            data.paths.insert(
+                Id(id),
+                ItemSummary {
+                    crate_id: 0,
+                    path: path.into_iter().map(str::to_string).collect(),
+                    kind,
+                },
+            );
+        }
+
+        let stats = DocStats::collect(&data, &MarkdownOptions::default());
+        assert_eq!(
+            stats,
+            DocStats {
+                modules: 2,
+                structs: 1,
+                enums: 0,
+                traits: 0,
+                functions: 2,
+                total_items: 5,
+                documented: 2,
+                undocumented: 3,
+            }
+        );
+        assert_eq!(stats.summary(), "2 modules, 1 struct, 2 functions");
+
+        // The counts leave out what the output leaves out
+        let summary = |options: MarkdownOptions| DocStats::collect(&data, &options).summary();
+        assert_eq!(
+            summary(MarkdownOptions::new().only_documented(true)),
+            "2 modules, 1 function"
+        );
+        assert_eq!(
+            summary(MarkdownOptions::new().exclude("demo::Config")),
+            "2 modules, 2 functions"
+        );
+        assert_eq!(
+            summary(MarkdownOptions::new().max_depth(Some(0))),
+            "1 module, 1 struct"
+        );
+        assert_eq!(
+            DocStats::collect_module_subtree(&data, "demo::net", &MarkdownOptions::default())
+                .map(|stats| stats.summary()),
+            Some("1 module, 2 functions".to_string())
+        );
+    }
+}
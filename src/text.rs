@@ -0,0 +1,303 @@
+/// Converts Markdown rendered by this crate into plain text, for tools and
+/// terminals that don't understand Markdown.
+///
+/// Level 1 and 2 headings are underlined with `=` and `-`, deeper headings become
+/// plain lines, tables are laid out in aligned columns and code blocks are
+/// indented by four spaces. Backticks, emphasis and quote markers are dropped, and
+/// links are reduced to their text.
+pub fn markdown_to_plain_text(markdown: &str) -> String {
+    let mut output = String::new();
+    let mut lines = markdown.lines().peekable();
+    // The open fence's character and length
+    let mut open_fence: Option<(char, usize)> = None;
+    // Whether the open fence is a list item's code block, and so is already
+    // indented, and the bullet to put before its first line
+    let mut list_fence = false;
+    let mut pending_bullet: Option<String> = None;
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+
+        if let Some((fence_char, fence_len)) = open_fence {
+            let fence = fence_char.to_string().repeat(fence_len);
+            if trimmed.starts_with(&fence)
+                && trimmed.trim_start_matches(fence_char).trim().is_empty()
+            {
+                open_fence = None;
+                if let Some(bullet) = pending_bullet.take() {
+                    output.push_str(bullet.trim_end());
+                    output.push('\n');
+                }
+            } else if line.is_empty() {
+                output.push('\n');
+            } else if let Some(bullet) = pending_bullet.take() {
+                output.push_str(&format!("{}{}\n", bullet, line.trim_start()));
+            } else if list_fence {
+                output.push_str(&format!("{}\n", line));
+            } else {
+                output.push_str(&format!("    {}\n", line));
+            }
+            continue;
+        }
+
+        // A code block opening a list item, as in method listings
+        let (bullet, fence_line) = match trimmed.strip_prefix("- ") {
+            Some(rest) => (Some(&line[..line.len() - rest.len()]), rest.trim_start()),
+            None => (None, trimmed),
+        };
+        if let Some(fence_char) = ['`', '~'].into_iter().find(|&c| fence_line.starts_with(c)) {
+            let fence_len = fence_line.len() - fence_line.trim_start_matches(fence_char).len();
+            if fence_len >= 3 {
+                open_fence = Some((fence_char, fence_len));
+                list_fence = bullet.is_some();
+                pending_bullet = bullet.map(str::to_string);
+                continue;
+            }
+        }
+
+        if trimmed.starts_with('|') {
+            let mut rows = vec![table_cells(trimmed)];
+            while let Some(next) = lines.peek().map(|next| next.trim_start()) {
+                if !next.starts_with('|') {
+                    break;
+                }
+                rows.push(table_cells(next));
+                lines.next();
+            }
+            push_table(&mut output, &rows);
+            continue;
+        }
+
+        let hashes = trimmed.len() - trimmed.trim_start_matches('#').len();
+        if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            let heading = strip_inline(trimmed[hashes..].trim());
+            output.push_str(&heading);
+            output.push('\n');
+            match hashes {
+                1 => output.push_str(&format!("{}\n", "=".repeat(heading.chars().count()))),
+                2 => output.push_str(&format!("{}\n", "-".repeat(heading.chars().count()))),
+                _ => {}
+            }
+            continue;
+        }
+
+        let line = line
+            .strip_prefix("> ")
+            .or_else(|| line.strip_prefix('>'))
+            .unwrap_or(line);
+        output.push_str(&strip_inline(line));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Splits a table row into its cells, leaving escaped `\|` pipes inside cells.
+fn table_cells(row: &str) -> Vec<String> {
+    let row = row.trim().trim_start_matches('|');
+    let row = row.strip_suffix('|').unwrap_or(row);
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = row.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell)),
+            _ => cell.push(c),
+        }
+    }
+    cells.push(cell);
+
+    cells.iter().map(|cell| strip_inline(cell.trim())).collect()
+}
+
+/// Writes table rows as columns padded to their widest cell, leaving out the
+/// `|---|` separator row.
+fn push_table(output: &mut String, rows: &[Vec<String>]) {
+    let is_separator = |row: &Vec<String>| {
+        row.iter()
+            .all(|cell| !cell.is_empty() && cell.chars().all(|c| matches!(c, '-' | ':')))
+    };
+    let rows: Vec<&Vec<String>> = rows.iter().filter(|row| !is_separator(row)).collect();
+
+    let columns = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = (0..columns)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+}
+
+/// Drops inline Markdown markup: backticks, bold and emphasis markers, `<br>`
+/// breaks and autolink brackets. Links and images are reduced to their text,
+/// followed by the URL in parentheses if it's absolute. Code spans are kept as is.
+fn strip_inline(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            let ticks = rest.len() - rest.trim_start_matches('`').len();
+            let after = &rest[ticks..];
+            match after.find(&rest[..ticks]) {
+                Some(end) => {
+                    output.push_str(&after[..end]);
+                    rest = &after[end + ticks..];
+                }
+                None => rest = after,
+            }
+        } else if let Some(after) = rest.strip_prefix("<br>") {
+            output.push(' ');
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            rest = after;
+        } else if c == '*'
+            && let Some(end) = emphasis_end(&rest[1..])
+        {
+            output.push_str(&strip_inline(&rest[1..end + 1]));
+            rest = &rest[end + 2..];
+        } else if c == '<'
+            && let Some((url, after)) = autolink(rest)
+        {
+            output.push_str(url);
+            rest = after;
+        } else if let Some((label, url, after)) = link(rest.strip_prefix('!').unwrap_or(rest)) {
+            output.push_str(&strip_inline(label));
+            if let Some(url) = url.filter(|url| url.contains("://") && c != '!') {
+                output.push_str(&format!(" ({})", url));
+            }
+            rest = after;
+        } else {
+            output.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    output
+}
+
+/// Finds the `*` closing single-asterisk emphasis that `text`, the text after an
+/// opening `*`, starts. Both markers must hug the emphasized text, as in `*word*`.
+fn emphasis_end(text: &str) -> Option<usize> {
+    if text.starts_with([' ', '*']) {
+        return None;
+    }
+    let end = text[..text.find('`').unwrap_or(text.len())].find('*')?;
+    (end > 0 && !text[..end].ends_with(' ')).then_some(end)
+}
+
+/// Splits an autolink like `<https://example.com>` into its URL and the text after it.
+fn autolink(text: &str) -> Option<(&str, &str)> {
+    let end = text.find('>')?;
+    let url = &text[1..end];
+    let (scheme, _) = url.split_once("://")?;
+    let is_url = !scheme.is_empty()
+        && scheme.chars().all(|c| c.is_ascii_alphabetic())
+        && !url.contains(char::is_whitespace);
+    is_url.then(|| (url, &text[end + 1..]))
+}
+
+/// Splits a link like `[label](url)`, `[label][ref]` or `[Path]` into its label,
+/// its inline URL if it has one, and the text after it.
+fn link(text: &str) -> Option<(&str, Option<&str>, &str)> {
+    let label_text = text.strip_prefix('[')?;
+    let mut depth = 0;
+    let end = label_text.char_indices().find_map(|(i, c)| match c {
+        '[' => {
+            depth += 1;
+            None
+        }
+        ']' if depth == 0 => Some(i),
+        ']' => {
+            depth -= 1;
+            None
+        }
+        _ => None,
+    })?;
+    let label = &label_text[..end];
+    let after = &label_text[end + 1..];
+    if let Some(target) = after.strip_prefix('(')
+        && let Some(close) = target.find(')')
+    {
+        let url = target[..close].split_whitespace().next().unwrap_or("");
+        return Some((label, Some(url), &target[close + 1..]));
+    }
+    if let Some(reference) = after.strip_prefix('[')
+        && let Some(close) = reference.find(']')
+    {
+        return Some((label, None, &reference[close + 1..]));
+    }
+    // A bare `[label]` is only a link if it's an intra-doc link to a Rust path
+    let is_path = |label: &str| {
+        !label.is_empty()
+            && label
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '!' | '(' | ')'))
+    };
+    is_path(label.trim_matches('`')).then_some((label, None, after))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{markdown_to_plain_text, strip_inline};
+
+    #[test]
+    fn links_and_images_are_reduced_to_their_text() {
+        assert_eq!(
+            strip_inline("See [the guide](https://example.com/guide \"Guide\") first."),
+            "See the guide (https://example.com/guide) first."
+        );
+        assert_eq!(
+            strip_inline("Built on [`Vec`](std::vec::Vec)."),
+            "Built on Vec."
+        );
+        assert_eq!(
+            strip_inline("Use [`Parser::parse`] or [Lexer]."),
+            "Use Parser::parse or Lexer."
+        );
+        assert_eq!(strip_inline("As [described][docs]."), "As described.");
+        assert_eq!(strip_inline("![Logo](docs/logo.png) Demo"), "Logo Demo");
+        assert_eq!(strip_inline("Values in [0, 1]."), "Values in [0, 1].");
+    }
+
+    #[test]
+    fn emphasis_and_autolinks_are_unwrapped() {
+        assert_eq!(strip_inline("*Private field*"), "Private field");
+        assert_eq!(strip_inline("**Note:** *very* fast"), "Note: very fast");
+        assert_eq!(strip_inline("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(
+            strip_inline("Docs at <https://docs.rs/demo>."),
+            "Docs at https://docs.rs/demo."
+        );
+        assert_eq!(strip_inline("Returns <T as Trait>"), "Returns <T as Trait>");
+    }
+
+    #[test]
+    fn code_spans_are_kept_verbatim() {
+        assert_eq!(
+            strip_inline("Takes `*const [u8]`<br>and `**ptr`"),
+            "Takes *const [u8] and **ptr"
+        );
+        assert_eq!(
+            markdown_to_plain_text("| Name | Docs |\n|---|---|\n| `x` | *Private field* |\n"),
+            "Name  Docs\nx     Private field\n"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeSet;
+
+use rustdoc_types::{
+    Crate, GenericArg, GenericArgs, GenericBound, Id, ItemEnum, Path, Static, Type,
+};
+
+/// Returns the sorted paths of items the crate refers to, in signatures or
+/// intra-doc links, that the JSON has no path information for. These are usually
+/// items from dependencies that weren't documented alongside the crate, so they
+/// can't be linked to.
+pub fn unresolved_paths(data: &Crate) -> Vec<String> {
+    let mut unresolved = BTreeSet::new();
+    let is_known = |id: &Id| data.paths.contains_key(id) || data.index.contains_key(id);
+
+    for item in data.index.values() {
+        for (link, id) in &item.links {
+            if !is_known(id) {
+                unresolved.insert(link.trim_matches('`').to_string());
+            }
+        }
+
+        let mut paths = Vec::new();
+        match &item.inner {
+            ItemEnum::StructField(type_) => collect_type_paths(type_, &mut paths),
+            ItemEnum::Function(function) => {
+                for (_, type_) in &function.sig.inputs {
+                    collect_type_paths(type_, &mut paths);
+                }
+                if let Some(output) = &function.sig.output {
+                    collect_type_paths(output, &mut paths);
+                }
+            }
+            ItemEnum::Impl(impl_) => {
+                if let Some(trait_) = &impl_.trait_ {
+                    collect_path_paths(trait_, &mut paths);
+                }
+                collect_type_paths(&impl_.for_, &mut paths);
+            }
+            ItemEnum::TypeAlias(type_alias) => collect_type_paths(&type_alias.type_, &mut paths),
+            ItemEnum::Constant { type_, .. } | ItemEnum::Static(Static { type_, .. }) => {
+                collect_type_paths(type_, &mut paths)
+            }
+            _ => {}
+        }
+        for path in paths {
+            if !is_known(&path.id) {
+                unresolved.insert(path.path.clone());
+            }
+        }
+    }
+
+    unresolved.into_iter().collect()
+}
+
+fn collect_type_paths<'a>(type_: &'a Type, paths: &mut Vec<&'a Path>) {
+    match type_ {
+        Type::ResolvedPath(path) => collect_path_paths(path, paths),
+        Type::DynTrait(dyn_trait) => {
+            for poly_trait in &dyn_trait.traits {
+                collect_path_paths(&poly_trait.trait_, paths);
+            }
+        }
+        Type::ImplTrait(bounds) => {
+            for bound in bounds {
+                if let GenericBound::TraitBound { trait_, .. } = bound {
+                    collect_path_paths(trait_, paths);
+                }
+            }
+        }
+        Type::Tuple(types) => {
+            for type_ in types {
+                collect_type_paths(type_, paths);
+            }
+        }
+        Type::Slice(type_)
+        | Type::Array { type_, .. }
+        | Type::Pat { type_, .. }
+        | Type::RawPointer { type_, .. }
+        | Type::BorrowedRef { type_, .. } => collect_type_paths(type_, paths),
+        Type::QualifiedPath {
+            self_type, trait_, ..
+        } => {
+            collect_type_paths(self_type, paths);
+            if let Some(trait_) = trait_ {
+                collect_path_paths(trait_, paths);
+            }
+        }
+        Type::FunctionPointer(function_pointer) => {
+            for (_, type_) in &function_pointer.sig.inputs {
+                collect_type_paths(type_, paths);
+            }
+            if let Some(output) = &function_pointer.sig.output {
+                collect_type_paths(output, paths);
+            }
+        }
+        Type::Generic(_) | Type::Primitive(_) | Type::Infer => {}
+    }
+}
+
+fn collect_path_paths<'a>(path: &'a Path, paths: &mut Vec<&'a Path>) {
+    paths.push(path);
+    match path.args.as_deref() {
+        Some(GenericArgs::AngleBracketed { args, .. }) => {
+            for arg in args {
+                if let GenericArg::Type(type_) = arg {
+                    collect_type_paths(type_, paths);
+                }
+            }
+        }
+        Some(GenericArgs::Parenthesized { inputs, output }) => {
+            for type_ in inputs {
+                collect_type_paths(type_, paths);
+            }
+            if let Some(output) = output {
+                collect_type_paths(output, paths);
+            }
+        }
+        Some(GenericArgs::ReturnTypeNotation) | None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustdoc_types::{GenericArg, GenericArgs, Id, ItemKind, ItemSummary};
+
+    use crate::tests::{function, item, resolved, struct_with_fields, test_crate};
+
+    use super::unresolved_paths;
+
+    #[test]
+    fn reports_paths_and_links_missing_from_the_json() {
+        // `fn load() -> Option<serde::Value>` linking to [`Config`] and [`anyhow::Error`]
+        let option_of_value = resolved(
+            "Option",
+            100,
+            Some(GenericArgs::AngleBracketed {
+                args: vec![GenericArg::Type(resolved("serde::Value", 200, None))],
+                constraints: Vec::new(),
+            }),
+        );
+        let mut load = item(2, "load", function(Vec::new(), Some(option_of_value)));
+        load.links.insert("`Config`".to_string(), Id(1));
+        load.links.insert("`anyhow::Error`".to_string(), Id(201));
+        let mut data = test_crate(
+            &[1, 2],
+            vec![item(1, "Config", struct_with_fields(&[], &[])), load],
+        );
+        data.paths.insert(
+            Id(100),
+            ItemSummary {
+                crate_id: 1,
+                path: vec![
+                    "core".to_string(),
+                    "option".to_string(),
+                    "Option".to_string(),
+                ],
+                kind: ItemKind::Enum,
+            },
+        );
+
+        assert_eq!(unresolved_paths(&data), ["anyhow::Error", "serde::Value"]);
+    }
+}